@@ -3,7 +3,14 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use governance_types::types::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use governance_types::types::{
+    BallotListResponse, BallotResponse, ConfigResponse, CreatorStatsResponse, DelegatorsResponse,
+    ExecuteMsg, ExtendedStatsResponse, InstantiateMsg, MigrateMsg, ProposalBreakdownResponse,
+    ProposalCategory, ProposalListResponse, ProposalResponse, QueryMsg, StatsResponse,
+    TallyResponse, VerifyStatsResponse, StatsSnapshot, StatsHistoryResponse, HooksResponse,
+    RewardPoolResponse, PendingRewardsResponse, ReceiveMsg, ProposalRevisionsResponse,
+};
+use governance_types::hooks::GovernanceHookExecuteMsg;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -14,4 +21,26 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(ProposalResponse), &out_dir);
+    export_schema(&schema_for!(ProposalListResponse), &out_dir);
+    export_schema(&schema_for!(ProposalCategory), &out_dir);
+    export_schema(&schema_for!(DelegatorsResponse), &out_dir);
+    export_schema(&schema_for!(BallotResponse), &out_dir);
+    export_schema(&schema_for!(BallotListResponse), &out_dir);
+    export_schema(&schema_for!(TallyResponse), &out_dir);
+    export_schema(&schema_for!(ExtendedStatsResponse), &out_dir);
+    export_schema(&schema_for!(CreatorStatsResponse), &out_dir);
+    export_schema(&schema_for!(ProposalBreakdownResponse), &out_dir);
+    export_schema(&schema_for!(VerifyStatsResponse), &out_dir);
+    export_schema(&schema_for!(StatsSnapshot), &out_dir);
+    export_schema(&schema_for!(StatsHistoryResponse), &out_dir);
+    export_schema(&schema_for!(HooksResponse), &out_dir);
+    export_schema(&schema_for!(GovernanceHookExecuteMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(RewardPoolResponse), &out_dir);
+    export_schema(&schema_for!(PendingRewardsResponse), &out_dir);
+    export_schema(&schema_for!(ProposalRevisionsResponse), &out_dir);
 }
@@ -1,6 +1,8 @@
-use cosmwasm_std::{Storage};
+use cosmwasm_std::{Decimal, Storage};
+use cw0::Duration;
+use governance_types::types::{ExecuteMsg, LateQuorum, ProposalMetadata, Threshold};
 use governance_types::errors::ContractError;
-use crate::state::{VoteStatus, read_config};
+use crate::state::{VoteStatus, read_config, may_load_vote, may_load_ballot, may_load_recompute};
 use cosmwasm_std::Addr;
 
 pub fn is_admin(storage: &dyn Storage, user: Addr) -> Result<bool, ContractError>{
@@ -10,29 +12,17 @@ pub fn is_admin(storage: &dyn Storage, user: Addr) -> Result<bool, ContractError
             return Ok(true);
         }
     }
-    return Ok(false);
+    Ok(false)
 }
 pub fn is_vote(storage: &dyn Storage, title: String) -> Result<bool, ContractError>{
-    let title = title.to_string();
-    let config = read_config(storage)?;
-    for some_title in config.votes_titles.clone() {
-        if some_title == title{
-            return Ok(true);
-        }
-    }
-    return Ok(false);
+    Ok(may_load_vote(storage, &title)?.is_some())
 }
 pub fn is_owner(storage: &dyn Storage, user: Addr) -> Result<bool, ContractError>{
     let config = read_config(storage)?;
-    return Ok(config.owner == user)
+    Ok(config.owner == user)
 }
-pub fn already_participate(vote: VoteStatus, voter: Addr) -> Result<bool, ContractError>{
-    for participate in vote.already_participate.clone() {
-        if participate == voter {
-            return Ok(true);
-        }
-    }
-    return Ok(false);
+pub fn already_participate(storage: &dyn Storage, title: &str, voter: &Addr) -> Result<bool, ContractError>{
+    Ok(may_load_ballot(storage, title, voter)?.is_some())
 }
 pub fn is_whitelisted(vote: VoteStatus, voter: Addr) -> Result<bool, ContractError>{
     for whitelisted_voter in vote.whitelist.clone() {
@@ -40,5 +30,149 @@ pub fn is_whitelisted(vote: VoteStatus, voter: Addr) -> Result<bool, ContractErr
             return Ok(true);
         }
     }
-    return Ok(false);
-}
\ No newline at end of file
+    Ok(false)
+}
+pub fn assert_valid_threshold(threshold: &Threshold, veto_threshold: Decimal) -> Result<(), ContractError>{
+    let valid_percentage = |percentage: Decimal| !percentage.is_zero() && percentage <= Decimal::one();
+    let valid = match *threshold {
+        Threshold::AbsoluteCount { weight } => weight > 0,
+        Threshold::AbsolutePercentage { percentage } => valid_percentage(percentage),
+        Threshold::ThresholdQuorum { threshold, quorum } => valid_percentage(threshold) && valid_percentage(quorum),
+    };
+    if !valid || !valid_percentage(veto_threshold) {
+        return Err(ContractError::WrongVotesPercentage {});
+    }
+    Ok(())
+}
+
+// Titles are storage keys, so they are kept short and printable.
+pub const MAX_TITLE_BYTES: usize = 128;
+pub const MAX_DESCRIPTION_BYTES: usize = 4096;
+pub const MAX_LINKS: usize = 10;
+pub const MAX_LINK_BYTES: usize = 256;
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_BYTES: usize = 32;
+
+pub fn assert_valid_title(title: &str) -> Result<(), ContractError>{
+    if title.trim().is_empty() {
+        return Err(ContractError::EmptyTitle {});
+    }
+    if title.len() > MAX_TITLE_BYTES {
+        return Err(ContractError::TitleTooLong {});
+    }
+    if title.chars().any(char::is_control) {
+        return Err(ContractError::InvalidCharacters {});
+    }
+    Ok(())
+}
+pub fn assert_valid_description(description: &str) -> Result<(), ContractError>{
+    if description.len() > MAX_DESCRIPTION_BYTES {
+        return Err(ContractError::DescriptionTooLong {});
+    }
+    // line breaks and tabs are fine in a description
+    if description.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+        return Err(ContractError::InvalidCharacters {});
+    }
+    Ok(())
+}
+pub fn assert_valid_links(links: &[String]) -> Result<(), ContractError>{
+    if links.len() > MAX_LINKS {
+        return Err(ContractError::TooManyLinks {});
+    }
+    for link in links {
+        if link.is_empty() || link.len() > MAX_LINK_BYTES || link.chars().any(|c| c.is_control() || c.is_whitespace()) {
+            return Err(ContractError::InvalidLink {});
+        }
+    }
+    Ok(())
+}
+pub fn assert_valid_tags(tags: &[String]) -> Result<(), ContractError>{
+    if tags.len() > MAX_TAGS {
+        return Err(ContractError::TooManyTags {});
+    }
+    for (i, tag) in tags.iter().enumerate() {
+        let valid_chars = tag.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'-');
+        if tag.is_empty() || tag.len() > MAX_TAG_BYTES || !valid_chars || tags[..i].contains(tag) {
+            return Err(ContractError::InvalidTag {});
+        }
+    }
+    Ok(())
+}
+pub fn assert_valid_metadata(metadata: &ProposalMetadata) -> Result<(), ContractError>{
+    assert_valid_description(&metadata.description)?;
+    assert_valid_links(&metadata.links)?;
+    assert_valid_tags(&metadata.tags)
+}
+// While the contract is emergency paused only the owner's way out is accepted.
+pub fn assert_not_emergency_paused(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ContractError>{
+    if matches!(msg, ExecuteMsg::EmergencyUnpause {} | ExecuteMsg::SetGuardian { .. }) {
+        return Ok(());
+    }
+    if read_config(storage)?.emergency_pause.is_some() {
+        return Err(ContractError::EmergencyPaused {});
+    }
+    Ok(())
+}
+// Proposals cannot change while RecomputeStats walks through them.
+pub fn assert_not_recomputing(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ContractError>{
+    let changes_proposals = matches!(msg,
+        ExecuteMsg::CreateNewVote { .. }
+        | ExecuteMsg::Vote { .. }
+        | ExecuteMsg::Pause { .. }
+        | ExecuteMsg::Unpause { .. }
+        | ExecuteMsg::ToogleWhitelist { .. }
+        | ExecuteMsg::ToogleRequiredCoin { .. }
+        | ExecuteMsg::Finalize { .. }
+        | ExecuteMsg::FinalizeExpired { .. }
+        | ExecuteMsg::Cancel { .. }
+        | ExecuteMsg::UpdateProposal { .. });
+    if changes_proposals && may_load_recompute(storage)?.is_some() {
+        return Err(ContractError::StatsRecomputeInProgress {});
+    }
+    Ok(())
+}
+// The pause cap is counted in the unit of the voting period.
+pub fn assert_valid_pause_cap(voting_period: &Option<Duration>, cap: &Option<Duration>) -> Result<(), ContractError>{
+    match (voting_period, cap) {
+        (Some(period), Some(cap)) if !same_unit(period, cap) => Err(ContractError::PauseCapUnitMismatch {}),
+        _ => Ok(()),
+    }
+}
+// Longest end a late quorum can set from the block reaching it, about a week in either unit.
+pub const MAX_LATE_QUORUM_BLOCKS: u64 = 100_800;
+pub const MAX_LATE_QUORUM_SECONDS: u64 = 604_800;
+pub fn assert_valid_late_quorum(voting_period: &Option<Duration>, late_quorum: &Option<LateQuorum>) -> Result<(), ContractError>{
+    if let Some(late_quorum) = late_quorum {
+        let period_unit = match voting_period {
+            Some(period) => same_unit(period, &late_quorum.window),
+            None => true,
+        };
+        if !period_unit || !same_unit(&late_quorum.window, &late_quorum.extension) {
+            return Err(ContractError::LateQuorumUnitMismatch {});
+        }
+        let too_long = match late_quorum.extension {
+            Duration::Height(blocks) => blocks > MAX_LATE_QUORUM_BLOCKS,
+            Duration::Time(seconds) => seconds > MAX_LATE_QUORUM_SECONDS,
+        };
+        if too_long {
+            return Err(ContractError::LateQuorumExtensionTooLong {});
+        }
+    }
+    Ok(())
+}
+fn same_unit(a: &Duration, b: &Duration) -> bool {
+    matches!((a, b), (Duration::Height(_), Duration::Height(_)) | (Duration::Time(_), Duration::Time(_)))
+}
+// Total voting power of a vote, the whitelist size for whitelisted votes. Percentage thresholds
+// are measured against it, so they need a known and non-empty one.
+pub fn total_power(threshold: &Threshold, whitelist_on: bool, whitelist: &[Addr]) -> Result<Option<u64>, ContractError>{
+    let total_power = if whitelist_on { Some(whitelist.len() as u64) } else { None };
+    if !matches!(threshold, Threshold::AbsoluteCount { .. }) {
+        match total_power {
+            None => return Err(ContractError::UnknownTotalPower {}),
+            Some(0) => return Err(ContractError::EmptyWhitelist {}),
+            Some(_) => {}
+        }
+    }
+    Ok(total_power)
+}
@@ -1,11 +1,30 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, to_binary};
+use cw2::{set_contract_version, CONTRACT};
+use semver::Version;
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, to_binary};
 use governance_types::errors::ContractError;
 use governance_types::types::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::queries::{query_get_vote, query_config, query_get_votes_titles, query_get_stats};
+use crate::queries::{query_get_vote, query_config, query_get_votes_titles, query_get_stats, query_get_delegation, query_get_delegators,
+    query_get_category, query_get_categories, query_list_proposals, query_reverse_proposals,
+    query_get_ballot, query_list_votes, query_voter_history, query_tally,
+    query_get_extended_stats, query_get_creator_stats, query_get_proposal_breakdown,
+    query_verify_stats, query_stats_at, query_stats_history, query_hooks,
+    query_reward_pool, query_pending_rewards, query_proposal_revisions,
+    query_proposals_by_tag};
 use crate::state::{Config, Stats, store_config, store_stats};
-use crate::execute::{ execute_new_vote, execute_vote, execute_pause, execute_unpause, execute_toogle_whitelist, execute_toogle_required_coin};
+use crate::migrations::migrate_from;
+use crate::stats::record_stats_snapshot;
+use crate::execute::{ execute_new_vote, execute_vote, execute_pause, execute_unpause, execute_toogle_whitelist, execute_toogle_required_coin, execute_finalize, execute_finalize_expired, execute_cancel, execute_update_proposal, execute_delegate, execute_undelegate,
+    execute_set_category, execute_remove_category, execute_recompute_stats, execute_add_hook, execute_remove_hook,
+    execute_set_reward_pool, execute_fund_rewards, execute_receive, execute_claim_rewards,
+    execute_set_guardian, execute_emergency_pause, execute_emergency_unpause};
+use crate::assert::{assert_not_emergency_paused, assert_not_recomputing};
+use crate::hooks::HOOK_REPLY_ID;
+
+// version info for migration
+const CONTRACT_NAME: &str = "crates.io:governance-contract";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // Method is executed when a new contract instance is created. You can treat it as a constructor.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -18,18 +37,16 @@ pub fn instantiate(
     let config = Config {
         owner: info.sender.clone(),
         admins: msg.admins,
-        votes_titles: vec![]
+        guardian: None,
+        unpause_delay: None,
+        emergency_pause: None,
     };
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     store_config(deps.storage, &config)?;
-    let stats = Stats {
-        rejected_votes: 0,
-        accepted: 0,
-        not_resolved: 0,
-        paused: 0,
-        in_progress: 0
-    };
+    let stats = Stats::default();
     store_stats(deps.storage, &stats)?;
-    
+    record_stats_snapshot(deps.storage, _env.block.height)?;
+
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender)
@@ -40,32 +57,40 @@ pub fn instantiate(
 // It can be compared to Solidity NON view methods.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let height = env.block.height;
+    assert_not_emergency_paused(deps.storage, &msg)?;
+    assert_not_recomputing(deps.storage, &msg)?;
+    let response = dispatch(deps.branch(), env, info, msg)?;
+    // keep stats history in sync with whatever the message changed
+    record_stats_snapshot(deps.storage, height)?;
+    Ok(response)
+}
+
+fn dispatch(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
-    
     match msg {
         ExecuteMsg::CreateNewVote { 
             title,
-            min_votes_count,
-            required_votes_percentage,
-            whitelist_on,
-            whitelist,
-            required_coins_on,
-            required_coin,
+            category,
+            overrides,
+            metadata,
         } => execute_new_vote(
                 deps, 
                 _env, 
                 info,  
                 title,
-                min_votes_count,
-                required_votes_percentage,
-                whitelist_on,
-                whitelist,
-                required_coins_on,
-                required_coin,
+                category,
+                overrides,
+                metadata,
             ),
         ExecuteMsg::Vote { vote, title } => execute_vote(
             deps, 
@@ -97,6 +122,117 @@ pub fn execute(
             _env,
             info,
             title
+        ),
+        ExecuteMsg::Finalize { title } => execute_finalize(
+            deps,
+            _env,
+            info,
+            title
+        ),
+        ExecuteMsg::FinalizeExpired { limit } => execute_finalize_expired(
+            deps,
+            _env,
+            info,
+            limit
+        ),
+        ExecuteMsg::Cancel { title, reason } => execute_cancel(
+            deps,
+            _env,
+            info,
+            title,
+            reason
+        ),
+        ExecuteMsg::UpdateProposal { title, update } => execute_update_proposal(
+            deps,
+            _env,
+            info,
+            title,
+            update
+        ),
+        ExecuteMsg::Delegate { to } => execute_delegate(
+            deps,
+            _env,
+            info,
+            to
+        ),
+        ExecuteMsg::Undelegate {} => execute_undelegate(
+            deps,
+            _env,
+            info
+        ),
+        ExecuteMsg::SetCategory { name, category } => execute_set_category(
+            deps,
+            _env,
+            info,
+            name,
+            category
+        ),
+        ExecuteMsg::RemoveCategory { name } => execute_remove_category(
+            deps,
+            _env,
+            info,
+            name
+        ),
+        ExecuteMsg::RecomputeStats { limit } => execute_recompute_stats(
+            deps,
+            _env,
+            info,
+            limit
+        ),
+        ExecuteMsg::AddHook { addr, policy } => execute_add_hook(
+            deps,
+            _env,
+            info,
+            addr,
+            policy
+        ),
+        ExecuteMsg::RemoveHook { addr } => execute_remove_hook(
+            deps,
+            _env,
+            info,
+            addr
+        ),
+        ExecuteMsg::SetRewardPool { asset, per_proposal } => execute_set_reward_pool(
+            deps,
+            _env,
+            info,
+            asset,
+            per_proposal
+        ),
+        ExecuteMsg::FundRewards {} => execute_fund_rewards(
+            deps,
+            _env,
+            info
+        ),
+        ExecuteMsg::Receive(wrapper) => execute_receive(
+            deps,
+            _env,
+            info,
+            wrapper
+        ),
+        ExecuteMsg::SetGuardian { guardian, unpause_delay } => execute_set_guardian(
+            deps,
+            _env,
+            info,
+            guardian,
+            unpause_delay
+        ),
+        ExecuteMsg::EmergencyPause {} => execute_emergency_pause(
+            deps,
+            _env,
+            info
+        ),
+        ExecuteMsg::EmergencyUnpause {} => execute_emergency_unpause(
+            deps,
+            _env,
+            info
+        ),
+        ExecuteMsg::ClaimRewards { start_after, limit } => execute_claim_rewards(
+            deps,
+            _env,
+            info,
+            start_after,
+            limit
         )
     }
 }
@@ -106,7 +242,7 @@ pub fn execute(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps, 
-    _env: Env, 
+    env: Env, 
     msg: QueryMsg
 ) -> Result<Binary, ContractError> {
     match msg {
@@ -126,14 +262,143 @@ pub fn query(
             Ok(to_binary(&query_get_stats(deps)?)?)
             // return stats
         }
+        QueryMsg::GetDelegation { address } => {
+            Ok(to_binary(&query_get_delegation(deps, address)?)?)
+            // return address delegate
+        }
+        QueryMsg::GetDelegators { delegate } => {
+            Ok(to_binary(&query_get_delegators(deps, delegate)?)?)
+            // return delegate delegators and power
+        }
+        QueryMsg::GetCategory { name } => {
+            Ok(to_binary(&query_get_category(deps, name)?)?)
+            // return specific category
+        }
+        QueryMsg::GetCategories {} => {
+            Ok(to_binary(&query_get_categories(deps)?)?)
+            // return all categories names
+        }
+        QueryMsg::ListProposals { start_after, limit, status, creator } => {
+            Ok(to_binary(&query_list_proposals(deps, start_after, limit, status, creator)?)?)
+            // return page of proposals summaries
+        }
+        QueryMsg::ReverseProposals { start_before, limit, status, creator } => {
+            Ok(to_binary(&query_reverse_proposals(deps, start_before, limit, status, creator)?)?)
+            // return page of proposals summaries in descending order
+        }
+        QueryMsg::GetBallot { title, voter } => {
+            Ok(to_binary(&query_get_ballot(deps, title, voter)?)?)
+            // return voter choice on specific vote
+        }
+        QueryMsg::ListVotes { title, start_after, limit } => {
+            Ok(to_binary(&query_list_votes(deps, title, start_after, limit)?)?)
+            // return page of ballots on specific vote
+        }
+        QueryMsg::VoterHistory { voter, start_after, limit } => {
+            Ok(to_binary(&query_voter_history(deps, voter, start_after, limit)?)?)
+            // return page of voter ballots
+        }
+        QueryMsg::Tally { title } => {
+            Ok(to_binary(&query_tally(deps, env, title)?)?)
+            // return live tally and projected outcome
+        }
+        QueryMsg::GetExtendedStats {} => {
+            Ok(to_binary(&query_get_extended_stats(deps)?)?)
+            // return aggregated votes and turnout
+        }
+        QueryMsg::GetCreatorStats { creator } => {
+            Ok(to_binary(&query_get_creator_stats(deps, creator)?)?)
+            // return stats of proposals created by address
+        }
+        QueryMsg::GetProposalBreakdown { title } => {
+            Ok(to_binary(&query_get_proposal_breakdown(deps, title)?)?)
+            // return votes shares of specific vote
+        }
+        QueryMsg::VerifyStats {} => {
+            Ok(to_binary(&query_verify_stats(deps)?)?)
+            // return differences found by the last completed recompute
+        }
+        QueryMsg::StatsAt { height } => {
+            Ok(to_binary(&query_stats_at(deps, height)?)?)
+            // return stats as of specific block height
+        }
+        QueryMsg::StatsHistory { start_after, limit } => {
+            Ok(to_binary(&query_stats_history(deps, start_after, limit)?)?)
+            // return page of recorded stats snapshots
+        }
+        QueryMsg::Hooks {} => {
+            Ok(to_binary(&query_hooks(deps)?)?)
+            // return registered hooks and their failure policy
+        }
+        QueryMsg::RewardPool {} => {
+            Ok(to_binary(&query_reward_pool(deps)?)?)
+            // return reward pool configuration and balances
+        }
+        QueryMsg::PendingRewards { address, start_after, limit } => {
+            Ok(to_binary(&query_pending_rewards(deps, address, start_after, limit)?)?)
+            // return rewards claimable by address
+        }
+        QueryMsg::ProposalRevisions { title, start_after, limit } => {
+            Ok(to_binary(&query_proposal_revisions(deps, title, start_after, limit)?)?)
+            // return page of edits made to specific vote
+        }
+        QueryMsg::ProposalsByTag { tag, start_after, limit } => {
+            Ok(to_binary(&query_proposals_by_tag(deps, tag, start_after, limit)?)?)
+            // return page of proposals summaries with specific tag
+        }
     }
 }
 
+// Called back when a hook registered with HookPolicy::Ignore fails, its changes are
+// already reverted so the governance action goes through.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(
+pub fn reply(
     _deps: DepsMut,
     _env: Env,
-    _msg: MigrateMsg,
-) -> StdResult<Response> {
-    Ok(Response::default())
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    if msg.id != HOOK_REPLY_ID {
+        return Err(ContractError::UnknownReplyId {});
+    }
+    let error = msg.result.into_result().err().unwrap_or_default();
+    Ok(Response::new()
+        .add_attribute("action", "hook failed")
+        .add_attribute("error", error)
+    )
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let current = parse_version(CONTRACT_VERSION)?;
+    let stored = CONTRACT.may_load(deps.storage)?;
+    let from = match stored {
+        Some(info) => {
+            if info.contract != CONTRACT_NAME {
+                return Err(ContractError::WrongContractName {});
+            }
+            let from = parse_version(&info.version)?;
+            if from > current {
+                return Err(ContractError::CannotDowngrade {});
+            }
+            Some(from)
+        }
+        None => None,
+    };
+    let from_version = from.as_ref().map_or("none".to_string(), Version::to_string);
+    migrate_from(deps.storage, from, &msg)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    record_stats_snapshot(deps.storage, env.block.height)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+    )
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidContractVersion {})
 }
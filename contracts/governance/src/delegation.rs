@@ -0,0 +1,83 @@
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128};
+use crate::state::{VoteStatus, load_delegators, may_load_ballot, may_load_delegation};
+
+// Delegation chains deeper than this are cut off, the power below simply is not carried.
+pub const MAX_DELEGATION_DEPTH: u32 = 5;
+// Addresses in one delegation tree, top delegate included. Keeps the ballots of a vote bounded.
+pub const MAX_DELEGATION_TREE: u32 = 100;
+
+// Returns the voter and every delegator whose power the voter carries on this vote.
+// Delegators who already cast their own ballot keep their power (and their subtree).
+pub fn collect_represented(
+    storage: &dyn Storage,
+    title: &str,
+    vote: &VoteStatus,
+    voter: &Addr,
+) -> StdResult<Vec<Addr>> {
+    let mut represented = vec![voter.clone()];
+    collect_delegators(storage, title, vote, voter, MAX_DELEGATION_DEPTH, &mut represented)?;
+    Ok(represented)
+}
+
+fn collect_delegators(
+    storage: &dyn Storage,
+    title: &str,
+    vote: &VoteStatus,
+    delegate: &Addr,
+    depth: u32,
+    represented: &mut Vec<Addr>,
+) -> StdResult<()> {
+    if depth == 0 {
+        return Ok(());
+    }
+    for delegator in load_delegators(storage, delegate)? {
+        if may_load_ballot(storage, title, &delegator)?.is_some() {
+            continue;
+        }
+        if vote.whitelist_on && !vote.whitelist.contains(&delegator) {
+            continue;
+        }
+        represented.push(delegator.clone());
+        collect_delegators(storage, title, vote, &delegator, depth - 1, represented)?;
+    }
+    Ok(())
+}
+
+// Power of an address outside of any vote: itself plus its delegators, up to MAX_DELEGATION_DEPTH.
+pub fn delegated_power(storage: &dyn Storage, delegate: &Addr, depth: u32) -> StdResult<Uint128> {
+    let mut power = Uint128::new(1);
+    if depth == 0 {
+        return Ok(power);
+    }
+    for delegator in load_delegators(storage, delegate)? {
+        power = power.checked_add(delegated_power(storage, &delegator, depth - 1)?)?;
+    }
+    Ok(power)
+}
+
+// Top delegate of the tree the address belongs to.
+pub fn delegation_root(storage: &dyn Storage, address: &Addr) -> StdResult<Addr> {
+    let mut current = address.clone();
+    while let Some(next) = may_load_delegation(storage, &current)? {
+        current = next;
+    }
+    Ok(current)
+}
+
+// Levels of delegators below the address, 0 when nobody delegates to it.
+pub fn subtree_height(storage: &dyn Storage, address: &Addr) -> StdResult<u32> {
+    let mut height = 0;
+    for delegator in load_delegators(storage, address)? {
+        height = height.max(subtree_height(storage, &delegator)? + 1);
+    }
+    Ok(height)
+}
+
+// Number of addresses in the tree below the address, the address itself included.
+pub fn subtree_size(storage: &dyn Storage, address: &Addr) -> StdResult<u32> {
+    let mut size = 1;
+    for delegator in load_delegators(storage, address)? {
+        size += subtree_size(storage, &delegator)?;
+    }
+    Ok(size)
+}
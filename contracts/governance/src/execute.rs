@@ -1,187 +1,363 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{from_binary, BankMsg, BlockInfo, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage, Uint128, Uint64};
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
+use cw0::{Duration, Expiration};
 use governance_types::errors::ContractError;
-use crate::state::{ VoteStatus, store_vote, update_config, may_load_vote, update_vote };
-use cosmwasm_std::{Addr, Coin};
-use crate::assert::{is_admin, is_owner, is_vote, already_participate, is_whitelisted};
-use crate::stats::{add_in_progress, set_to_paused, set_to_un_paused};
+use governance_types::events::{GovernanceEvent, TallyAttributes};
+use governance_types::hooks::{GovernanceHookMsg, HookPolicy};
+use governance_types::types::{Outcome, ProposalCategory, CategoryField, VoteOverrides, ReceiveMsg, RewardAsset,
+    RefundPolicy, ProposalUpdate, ProposalRevision, ProposalMetadata, EmergencyPauseState};
+use crate::state::{ VoteStatus, BlockStamp, Ballot, store_vote, read_config, update_config, may_load_vote, update_vote, load_vote,
+    may_load_ballot, store_ballot, may_load_cast_via, store_cast_via, remove_cast_via,
+    may_load_delegation, store_delegation, remove_delegation, may_load_category, store_category, remove_category,
+    Stats, RecomputeProgress, RecomputeReport, clear_creator_stats, store_last_recompute, read_stats, range_votes, range_ended_votes, store_stats, may_load_recompute, store_recompute, remove_recompute,
+    may_load_hook, store_hook, remove_hook, RewardPool, may_load_reward_pool, store_reward_pool,
+    store_revision, store_tags, remove_tags,
+    load_emergency_pause_count, store_emergency_pause };
+use cosmwasm_std::Addr;
+use crate::assert::{is_admin, is_owner, is_vote, already_participate, is_whitelisted, assert_valid_threshold, total_power,
+    assert_valid_pause_cap, assert_valid_late_quorum, assert_valid_title, assert_valid_metadata, assert_valid_description, assert_valid_links, assert_valid_tags};
+use crate::stats::{stats_mismatches, add_in_progress, set_to_paused, set_to_un_paused, set_to_accepted, set_to_rejected, set_to_resolved, set_to_vetoed,
+    add_ballot, add_created, add_finalized_turnout, add_creator_outcome, accumulate, accumulate_creator, set_to_cancelled};
+use crate::tally::{compute_outcome, add_to_tally, remove_from_tally, quorum_reached, remaining_period, extend_expiration,
+    pause_cap_left, apply_emergency_pauses};
+use crate::delegation::{collect_represented, delegation_root, subtree_height, subtree_size, MAX_DELEGATION_DEPTH, MAX_DELEGATION_TREE};
+use crate::hooks::prepare_hooks;
+use crate::rewards::{allot_rewards, claimable_rewards, reward_msg, settle_claim};
+
+const DEFAULT_RECOMPUTE_LIMIT: u32 = 30;
+const MAX_RECOMPUTE_LIMIT: u32 = 100;
+const DEFAULT_FINALIZE_LIMIT: u32 = 10;
+const MAX_FINALIZE_LIMIT: u32 = 30;
+const DEFAULT_CLAIM_LIMIT: u32 = 30;
+const MAX_CLAIM_LIMIT: u32 = 100;
 
 pub fn execute_new_vote(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     title: String,
-    min_votes_count: i32,
-    required_votes_percentage: i32,
-    whitelist_on: bool,
-    whitelist: Vec<Addr>,
-    required_coins_on: bool,
-    required_coins: Coin,
-) -> Result<Response, ContractError> {
-    if required_votes_percentage > 100 {
-        return Err(ContractError::WrongVotesPercentage {});
-    }
-    if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-        if is_admin(deps.storage, info.sender.clone()) != Ok(true) {
-            return Err(ContractError::SenderIsNotAdmin {});
-        }
+    category_name: String,
+    overrides: VoteOverrides,
+    metadata: ProposalMetadata,
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
     }
+    assert_valid_title(&title)?;
+    assert_valid_metadata(&metadata)?;
     if is_vote(deps.storage, title.clone()) == Ok(true) {
         return Err(ContractError::VoteAlreadyExist {});
     }
+    let category = may_load_category(deps.storage, &category_name)?
+        .ok_or(ContractError::CannotFindCategory {})?;
+    let min_votes_count = pick(overrides.min_votes_count, category.min_votes_count, CategoryField::MinVotesCount, &category)?;
+    let threshold = pick(overrides.threshold, category.threshold.clone(), CategoryField::Threshold, &category)?;
+    let veto_threshold = pick(overrides.veto_threshold, category.veto_threshold, CategoryField::VetoThreshold, &category)?;
+    let voting_period = pick(overrides.voting_period.map(Some), category.voting_period, CategoryField::VotingPeriod, &category)?;
+    let whitelist_on = pick(overrides.whitelist_on, category.whitelist_on, CategoryField::Whitelist, &category)?;
+    let whitelist = pick(overrides.whitelist, category.whitelist.clone(), CategoryField::Whitelist, &category)?;
+    let required_coins_on = pick(overrides.required_coins_on, category.required_coins_on, CategoryField::RequiredCoin, &category)?;
+    let required_coins = pick(overrides.required_coin, category.required_coin.clone(), CategoryField::RequiredCoin, &category)?;
+    assert_valid_threshold(&threshold, veto_threshold)?;
+    assert_valid_pause_cap(&voting_period, &category.max_pause_extension)?;
+    assert_valid_late_quorum(&voting_period, &category.late_quorum)?;
+    // whitelisted votes are one address one vote, so the whitelist is the whole voting power
+    let total_power = total_power(&threshold, whitelist_on, &whitelist)?;
+    if let Some(deposit) = &category.deposit {
+        let funds = info.funds.iter().find(|x| x.denom == deposit.denom);
+        match funds {
+            Some(x) if x.amount >= deposit.amount => {}
+            _ => return Err(ContractError::InsufficientDeposit {}),
+        }
+    }
+    let expires = voting_period.map_or(Expiration::Never {}, |period| period.after(&env.block));
     let voter = VoteStatus {
         creator: info.sender.clone(),
         paused: false,
-        votes_for: 0,
-        votes_against: 0,
-        votes_abstain: 0,
+        votes_for: Uint128::zero(),
+        votes_against: Uint128::zero(),
+        votes_abstain: Uint128::zero(),
+        votes_no_with_veto: Uint128::zero(),
         required_coins_on,
         required_coins,
         min_votes_count,
-        required_votes_percentage,
-        already_participate: Vec::new(),
+        threshold,
+        veto_threshold,
+        total_power,
+        ballots: Uint64::zero(),
         whitelist_on,
         whitelist,
+        outcome: None,
+        category: category_name.clone(),
+        expires,
+        deposit: category.deposit,
+        cancel_refund: category.cancel_refund,
+        cancel_reason: None,
+        description: metadata.description,
+        links: metadata.links,
+        tags: metadata.tags.clone(),
+        revision: 0,
+        original_expires: Some(expires),
+        max_pause_extension: category.max_pause_extension,
+        pause_extension: 0,
+        paused_at: None,
+        late_quorum: category.late_quorum,
+        quorum_reached_at: None,
+        emergency_pauses_seen: load_emergency_pause_count(deps.storage)?,
     };
-    update_config(deps.storage, |mut config| -> Result<_, ContractError> {
-        config.votes_titles.push(title.clone());
-        Ok(config)
-    })?;
     store_vote(deps.storage, &title, voter)?;
+    store_tags(deps.storage, &title, &metadata.tags)?;
     add_in_progress(deps.storage)?;
+    add_created(deps.storage, &info.sender)?;
+
+    let hooks = prepare_hooks(deps.storage, GovernanceHookMsg::ProposalCreated {
+        title: title.clone(),
+        creator: info.sender.clone(),
+        category: category_name.clone(),
+    })?;
+    let event = GovernanceEvent::ProposalCreated {
+        title,
+        creator: info.sender,
+        category: category_name,
+    };
+    Ok(Response::new()
+        .add_attribute("action", "Added")
+        .add_event(event.to_event())
+        .add_submessages(hooks)
+    )
+}
 
-    Ok(Response::new().add_attribute("action", "Added"))
+// Takes the override when the category allows it, the category default otherwise.
+fn pick<T>(value: Option<T>, default: T, field: CategoryField, category: &ProposalCategory) -> Result<T, ContractError> {
+    match value {
+        Some(value) if category.overridable.contains(&field) => Ok(value),
+        Some(_) => Err(ContractError::FieldNotOverridable {}),
+        None => Ok(default),
+    }
 }
 pub fn execute_vote (
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     user_vote: String,
     title: String
 ) -> Result<Response, ContractError> {
-    let vote = may_load_vote(deps.storage, &title)?;
-    if None == vote{
-        return Err(ContractError::CannotFindVote {});
+    let vote = load_current_vote(deps.storage, &title)?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
     }
-    let vote = vote.unwrap();
-    if Ok(true) == already_participate(vote.clone(), info.sender.clone()) {
+    if already_participate(deps.storage, &title, &info.sender)? {
         return Err(ContractError::VoterAlreadyParticipate {});
     }
     if vote.paused {
         return Err(ContractError::VoteIsPaused {});
     }
-    if vote.whitelist_on {
-        if Ok(true) != is_whitelisted(vote.clone(), info.sender.clone()){
-            if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-                return Err(ContractError::SenderIsNotWhitelisted {});
-            }
-        }
+    if vote.expires.is_expired(&env.block) {
+        return Err(ContractError::VoteExpired {});
     }
-    let v = vote.clone();
-    if vote.clone().required_coins_on {
-        let mut finded = false;
-        let mut index = 0;
-        for (i, x) in info.funds.iter().enumerate() {
-            if x.denom == v.required_coins.denom{
-                finded = true;
-                index = i;
-                break;
-            }
-        }
-        if finded == false{
-            return Err(ContractError::SenderDoNotHaveEnoughAmount {});
+    if vote.whitelist_on
+        && Ok(true) != is_whitelisted(vote.clone(), info.sender.clone())
+        && is_owner(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotWhitelisted {});
+    }
+    if vote.required_coins_on {
+        let funds = info.funds.iter().find(|x| x.denom == vote.required_coins.denom);
+        match funds {
+            Some(x) if x.amount >= vote.required_coins.amount => {}
+            _ => return Err(ContractError::SenderDoNotHaveEnoughAmount {}),
         }
-        if info.funds[index].amount < vote.required_coins.amount{
-            return Err(ContractError::SenderDoNotHaveEnoughAmount {});
-        }  
-    }
-    return match user_vote.as_str() {
-        "For" => vote_for(deps, info.sender, title),
-        "Against" => vote_against(deps, info.sender, title),
-        "Abstain" => vote_abstain(deps, info.sender, title),
+    }
+    let action = match user_vote.as_str() {
+        "For" => "execute vote for",
+        "Against" => "execute vote against",
+        "Abstain" => "execute vote abstain",
+        "NoWithVeto" => "execute vote no with veto",
         _ => return Err(ContractError::VoteNotValid {}),
     };
+    let power = cast_vote(deps.branch(), info.sender.clone(), title.clone(), user_vote.clone())?;
+    let mut vote = load_vote(deps.storage, &title)?;
+    let mut response = Response::new().add_attribute("action", action);
+    if vote.quorum_reached_at.is_none() && quorum_reached(&vote)? {
+        vote.quorum_reached_at = Some(BlockStamp { height: env.block.height, time: env.block.time });
+        if let Some(expires) = late_quorum_deadline(&vote, &env.block)? {
+            vote.expires = expires;
+            response = response.add_attribute("late_quorum_extension", expires.to_string());
+        }
+        store_vote(deps.storage, &title, vote.clone())?;
+    }
+    let hooks = prepare_hooks(deps.storage, GovernanceHookMsg::VoteCast {
+        title: title.clone(),
+        voter: info.sender.clone(),
+        vote: user_vote.clone(),
+        power,
+    })?;
+    let event = GovernanceEvent::VoteCast {
+        title,
+        voter: info.sender,
+        vote: user_vote,
+        power,
+        tally: tally_attributes(&vote),
+    };
+    Ok(response
+        .add_event(event.to_event())
+        .add_submessages(hooks)
+    )
+}
+// New end of a vote whose quorum was just reached inside the late quorum window,
+// the vote stays open for at least the configured extension.
+fn late_quorum_deadline(vote: &VoteStatus, block: &BlockInfo) -> Result<Option<Expiration>, ContractError> {
+    let late_quorum = match vote.late_quorum.as_ref() {
+        Some(late_quorum) => late_quorum,
+        None => return Ok(None),
+    };
+    let in_window = match (remaining_period(&vote.expires, block), late_quorum.window) {
+        (Some(Duration::Height(left)), Duration::Height(window)) | (Some(Duration::Time(left)), Duration::Time(window)) => left < window,
+        _ => false,
+    };
+    if !in_window {
+        return Ok(None);
+    }
+    let extended = match (vote.expires, late_quorum.extension) {
+        (Expiration::AtHeight(_), Duration::Height(extension)) => extend_expiration(&Expiration::AtHeight(block.height), extension)?,
+        (Expiration::AtTime(_), Duration::Time(extension)) => extend_expiration(&Expiration::AtTime(block.time), extension)?,
+        _ => return Ok(None),
+    };
+    Ok(Some(extended).filter(|extended| *extended > vote.expires))
 }
 
-fn vote_for(deps: DepsMut, sender: Addr, title: String) -> Result<Response, ContractError> {
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
-        let mut vote_status = _vote_status.unwrap();
-        vote_status.votes_for+=1;
-        vote_status.already_participate.push(sender);
-        Ok(vote_status)
-    })?;
-    Ok(Response::new().add_attribute("action", "execute vote for"))
+fn tally_attributes(vote: &VoteStatus) -> TallyAttributes {
+    TallyAttributes {
+        votes_for: vote.votes_for,
+        votes_against: vote.votes_against,
+        votes_abstain: vote.votes_abstain,
+        votes_no_with_veto: vote.votes_no_with_veto,
+    }
 }
-fn vote_against(deps: DepsMut, sender: Addr, title: String) -> Result<Response, ContractError> {
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
-        let mut vote_status = _vote_status.unwrap();
-        vote_status.votes_against+=1;
-        vote_status.already_participate.push(sender);
-        Ok(vote_status)
-    })?;
-    Ok(Response::new().add_attribute("action", "execute vote against"))
+
+// Records the ballot with the sender's own power and the power of its delegators who did not
+// vote themselves. Power previously carried by another ballot (e.g. by the sender's delegate)
+// is moved over to the new ballot.
+fn cast_vote(deps: DepsMut, sender: Addr, title: String, user_vote: String) -> Result<Uint128, ContractError> {
+    let mut vote = load_vote(deps.storage, &title)?;
+    let represented = collect_represented(deps.storage, &title, &vote, &sender)?;
+    let mut moved = vec![];
+    for address in represented.iter() {
+        if let Some(previous) = may_load_cast_via(deps.storage, &title, address)? {
+            let mut ballot = may_load_ballot(deps.storage, &title, &previous)?
+                .ok_or(ContractError::FailedToUpdateData {})?;
+            remove_from_tally(&mut vote, &ballot.vote, Uint128::new(1))?;
+            moved.push(ballot.vote.clone());
+            ballot.power = ballot.power.checked_sub(Uint128::new(1))?;
+            store_ballot(deps.storage, &title, &previous, &ballot)?;
+        }
+        if *address == sender {
+            remove_cast_via(deps.storage, &title, address);
+        } else {
+            store_cast_via(deps.storage, &title, address, &sender)?;
+        }
+    }
+    let power = Uint128::from(represented.len() as u128);
+    add_to_tally(&mut vote, &user_vote, power)?;
+    vote.ballots = vote.ballots.checked_add(Uint64::new(1))?;
+    store_vote(deps.storage, &title, vote)?;
+    add_ballot(deps.storage, &user_vote, power, &moved)?;
+    store_ballot(deps.storage, &title, &sender, &Ballot { vote: user_vote, power })?;
+    Ok(power)
 }
-fn vote_abstain(deps: DepsMut, sender: Addr, title: String) -> Result<Response, ContractError> {
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
-        let mut vote_status = _vote_status.unwrap();
-        vote_status.votes_abstain+=1;
-        vote_status.already_participate.push(sender);
-        Ok(vote_status)
-    })?;
-    Ok(Response::new().add_attribute("action", "execute vote abstain"))
-}//execute_pause
+//execute_pause
 pub fn execute_pause(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     title: String
 ) -> Result<Response, ContractError> {
-    if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-        if is_admin(deps.storage, info.sender.clone()) != Ok(true) {
-            return Err(ContractError::SenderIsNotAdmin {});
-        }
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
     }
-    let vote = may_load_vote(deps.storage, &title)?;
-    if None == vote{
-        return Err(ContractError::CannotFindVote {});
+    let vote = load_current_vote(deps.storage, &title)?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
     }
-    let vote = &vote.unwrap();
     if vote.paused {
         return Ok(Response::new().add_attribute("action", "voting is already paused"))
     }
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
+    update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
         let mut vote_status = _vote_status.unwrap();
         vote_status.paused=true;
+        vote_status.paused_at = Some(BlockStamp { height: env.block.height, time: env.block.time });
         Ok(vote_status)
     })?;
     set_to_paused(deps.storage)?;
-    Ok(Response::new().add_attribute("action", "execute pause"))
+    let event = GovernanceEvent::ProposalPaused { title, sender: info.sender };
+    Ok(Response::new()
+        .add_attribute("action", "execute pause")
+        .add_event(event.to_event())
+    )
 }
 pub fn execute_unpause(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     title: String
 ) -> Result<Response, ContractError> {
-    if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-        if is_admin(deps.storage, info.sender.clone()) != Ok(true) {
-            return Err(ContractError::SenderIsNotAdmin {});
-        }
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
     }
-    let vote = may_load_vote(deps.storage, &title)?;
-    if None == vote{
-        return Err(ContractError::CannotFindVote {});
+    let vote = load_current_vote(deps.storage, &title)?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
     }
-    let vote = &vote.unwrap();
     if !vote.paused {
         return Ok(Response::new().add_attribute("action", "voting is already unpaused"))
     }
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
+    let extension = pause_extension(&vote, &env.block);
+    update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
         let mut vote_status = _vote_status.unwrap();
         vote_status.paused=false;
+        vote_status.paused_at = None;
+        vote_status.expires = extend_expiration(&vote_status.expires, extension)?;
+        vote_status.pause_extension += extension;
         Ok(vote_status)
     })?;
     set_to_un_paused(deps.storage)?;
-    Ok(Response::new().add_attribute("action", "execute unpause"))
+    let event = GovernanceEvent::ProposalUnpaused { title, sender: info.sender };
+    Ok(Response::new()
+        .add_attribute("action", "execute unpause")
+        .add_attribute("extended_by", extension.to_string())
+        .add_event(event.to_event())
+    )
+}
+// Blocks or seconds the vote spent paused, limited by what is left of its pause cap.
+fn pause_extension(vote: &VoteStatus, block: &BlockInfo) -> u64 {
+    let paused_at = match &vote.paused_at {
+        Some(paused_at) => paused_at,
+        None => return 0,
+    };
+    let paused_for = match vote.expires {
+        Expiration::AtHeight(_) => block.height.saturating_sub(paused_at.height),
+        Expiration::AtTime(_) => block.time.seconds().saturating_sub(paused_at.time.seconds()),
+        Expiration::Never {} => return 0,
+    };
+    paused_for.min(pause_cap_left(vote))
+}
+// Loads a vote with the emergency pauses it has not seen yet added to its end.
+fn load_current_vote(storage: &mut dyn Storage, title: &str) -> Result<VoteStatus, ContractError> {
+    let mut vote = may_load_vote(storage, title)?
+        .ok_or(ContractError::CannotFindVote {})?;
+    if apply_emergency_pauses(storage, &mut vote)? {
+        store_vote(storage, title, vote.clone())?;
+    }
+    Ok(vote)
 }
 pub fn execute_toogle_whitelist(
     deps: DepsMut,
@@ -189,21 +365,30 @@ pub fn execute_toogle_whitelist(
     info: MessageInfo,
     title: String
 ) -> Result<Response, ContractError> {
-    if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-        if is_admin(deps.storage, info.sender.clone()) != Ok(true) {
-            return Err(ContractError::SenderIsNotAdmin {});
-        }
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
     }
-    let vote = may_load_vote(deps.storage, &title)?;
-    if None == vote{
-        return Err(ContractError::CannotFindVote {});
+    let vote = may_load_vote(deps.storage, &title)?
+        .ok_or(ContractError::CannotFindVote {})?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
     }
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
+    }
+    let vote = update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
         let mut vote_status = _vote_status.unwrap();
-        vote_status.whitelist_on != vote_status.whitelist_on;
+        vote_status.whitelist_on = !vote_status.whitelist_on;
+        // quorum is measured against the whitelist only while it is on
+        vote_status.total_power = total_power(&vote_status.threshold, vote_status.whitelist_on, &vote_status.whitelist)?;
         Ok(vote_status)
     })?;
-    Ok(Response::new().add_attribute("action", "execute toogle whitelist"))
+    let event = GovernanceEvent::WhitelistToggled { title, enabled: vote.whitelist_on };
+    Ok(Response::new()
+        .add_attribute("action", "execute toogle whitelist")
+        .add_event(event.to_event())
+    )
 }
 pub fn execute_toogle_required_coin(
     deps: DepsMut,
@@ -211,19 +396,622 @@ pub fn execute_toogle_required_coin(
     info: MessageInfo,
     title: String
 ) -> Result<Response, ContractError> {
-    if is_owner(deps.storage, info.sender.clone()) != Ok(true) {
-        if is_admin(deps.storage, info.sender.clone()) != Ok(true) {
-            return Err(ContractError::SenderIsNotAdmin {});
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    let vote = may_load_vote(deps.storage, &title)?
+        .ok_or(ContractError::CannotFindVote {})?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
+    }
+    let vote = update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
+        let mut vote_status = _vote_status.unwrap();
+        vote_status.required_coins_on = !vote_status.required_coins_on;
+        Ok(vote_status)
+    })?;
+    let event = GovernanceEvent::RequiredCoinToggled { title, enabled: vote.required_coins_on };
+    Ok(Response::new()
+        .add_attribute("action", "execute toogle required coin")
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_finalize(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    let vote = load_current_vote(deps.storage, &title)?;
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
+    }
+    if vote.paused {
+        return Err(ContractError::VoteIsPaused {});
+    }
+    // only proposals without a voting period can be closed early
+    if !matches!(vote.expires, Expiration::Never {}) && !vote.expires.is_expired(&env.block) {
+        return Err(ContractError::VotingPeriodNotOver {});
+    }
+    let finalized = finalize_vote(deps, title, vote)?;
+    Ok(Response::new()
+        .add_attribute("action", "execute finalize")
+        .add_attributes(finalized.attributes)
+        .add_events(finalized.events)
+        .add_submessages(finalized.messages)
+    )
+}
+// Finalizes every proposal whose voting period is over, up to `limit` per call.
+pub fn execute_finalize_expired(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    limit: Option<u32>
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_FINALIZE_LIMIT).min(MAX_FINALIZE_LIMIT) as usize;
+    // paused proposals wait for an admin to unpause them
+    let ended = range_ended_votes(deps.storage, &env.block)
+        .filter(|item| !matches!(item, Ok((_, vote)) if vote.paused))
+        .map(|item| item.map(|(title, _)| title))
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut response = Response::new()
+        .add_attribute("action", "execute finalize expired");
+    let mut count = 0;
+    for title in ended {
+        // an emergency pause may push the end back once it is added
+        let vote = load_current_vote(deps.storage, &title)?;
+        if !vote.expires.is_expired(&env.block) {
+            continue;
         }
+        let finalized = finalize_vote(deps.branch(), title, vote)?;
+        response = response
+            .add_events(finalized.events)
+            .add_submessages(finalized.messages);
+        count += 1;
     }
-    let vote = may_load_vote(deps.storage, &title)?;
-    if None == vote{
-        return Err(ContractError::CannotFindVote {});
+    Ok(response.add_attribute("finalized", count.to_string()))
+}
+// Records the outcome of an open proposal, updates the stats and pays out the deposit and rewards.
+fn finalize_vote(deps: DepsMut, title: String, vote: VoteStatus) -> Result<Response, ContractError> {
+    let outcome = compute_outcome(&vote)?;
+    update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
+        let mut vote_status = _vote_status.unwrap();
+        vote_status.outcome = Some(outcome.clone());
+        Ok(vote_status)
+    })?;
+    let result = match outcome {
+        Outcome::Accepted => { set_to_accepted(deps.storage)?; "accepted" }
+        Outcome::Rejected => { set_to_rejected(deps.storage)?; "rejected" }
+        Outcome::NotResolved => { set_to_resolved(deps.storage)?; "not_resolved" }
+        Outcome::Vetoed => { set_to_vetoed(deps.storage)?; "vetoed" }
+    };
+    add_finalized_turnout(deps.storage, &vote)?;
+    add_creator_outcome(deps.storage, &vote, &outcome)?;
+    let rewards = allot_rewards(deps.storage, &title, &vote)?;
+    let hooks = prepare_hooks(deps.storage, GovernanceHookMsg::ProposalFinalized {
+        title: title.clone(),
+        outcome: outcome.clone(),
+    })?;
+    let event = GovernanceEvent::ProposalFinalized {
+        title,
+        outcome: outcome.clone(),
+        tally: tally_attributes(&vote),
+    };
+    let mut response = Response::new()
+        .add_attribute("outcome", result)
+        .add_attribute("rewards", rewards)
+        .add_event(event.to_event())
+        .add_submessages(hooks);
+    // vetoed proposals lose their deposit, every other outcome gets it back
+    if let Some(deposit) = vote.deposit {
+        response = match outcome {
+            Outcome::Vetoed => response.add_message(BankMsg::Burn { amount: vec![deposit] }),
+            _ => response.add_message(BankMsg::Send { to_address: vote.creator.to_string(), amount: vec![deposit] }),
+        };
+    }
+    Ok(response)
+}
+pub fn execute_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    reason: String
+) -> Result<Response, ContractError> {
+    let vote = load_current_vote(deps.storage, &title)?;
+    if vote.creator != info.sender
+        && is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
+    }
+    // a paused proposal is not over, its end moves back once it is unpaused
+    if !vote.paused && vote.expires.is_expired(&env.block) {
+        return Err(ContractError::VoteExpired {});
     }
-    update_vote(deps.storage, &title, |mut _vote_status | -> Result<_, ContractError> {
+    update_vote(deps.storage, &title, |_vote_status | -> Result<_, ContractError> {
         let mut vote_status = _vote_status.unwrap();
-        vote_status.required_coins_on != vote_status.required_coins_on;
+        vote_status.cancel_reason = Some(reason.clone());
         Ok(vote_status)
     })?;
-    Ok(Response::new().add_attribute("action", "execute toogle required coin"))
-}
\ No newline at end of file
+    set_to_cancelled(deps.storage, &vote)?;
+    let hooks = prepare_hooks(deps.storage, GovernanceHookMsg::ProposalCancelled {
+        title: title.clone(),
+        reason: reason.clone(),
+    })?;
+    let event = GovernanceEvent::ProposalCancelled { title, sender: info.sender, reason };
+    let mut response = Response::new()
+        .add_attribute("action", "execute cancel")
+        .add_event(event.to_event())
+        .add_submessages(hooks);
+    if let Some(deposit) = vote.deposit {
+        let refund = match vote.cancel_refund {
+            RefundPolicy::Always => true,
+            RefundPolicy::BeforeVotes => vote.ballots.is_zero(),
+            RefundPolicy::Never => false,
+        };
+        response = if refund {
+            response.add_message(BankMsg::Send { to_address: vote.creator.to_string(), amount: vec![deposit] })
+        } else {
+            response.add_message(BankMsg::Burn { amount: vec![deposit] })
+        };
+    }
+    Ok(response)
+}
+pub fn execute_update_proposal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    title: String,
+    update: ProposalUpdate
+) -> Result<Response, ContractError> {
+    let mut vote = load_current_vote(deps.storage, &title)?;
+    if vote.creator != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if vote.cancel_reason.is_some() {
+        return Err(ContractError::VoteCancelled {});
+    }
+    if vote.outcome.is_some() {
+        return Err(ContractError::VoteAlreadyFinalized {});
+    }
+    if vote.expires.is_expired(&env.block) {
+        return Err(ContractError::VoteExpired {});
+    }
+    if update == ProposalUpdate::default() {
+        return Err(ContractError::NothingToUpdate {});
+    }
+    // parameters follow the same override rules as CreateNewVote and are frozen by the first ballot
+    if update.changes_parameters() {
+        if !vote.ballots.is_zero() {
+            return Err(ContractError::VoteAlreadyStarted {});
+        }
+        let category = may_load_category(deps.storage, &vote.category)?
+            .ok_or(ContractError::CannotFindCategory {})?;
+        vote.min_votes_count = pick(update.min_votes_count, vote.min_votes_count, CategoryField::MinVotesCount, &category)?;
+        vote.whitelist_on = pick(update.whitelist_on, vote.whitelist_on, CategoryField::Whitelist, &category)?;
+        vote.whitelist = pick(update.whitelist.clone(), vote.whitelist, CategoryField::Whitelist, &category)?;
+        vote.required_coins_on = pick(update.required_coins_on, vote.required_coins_on, CategoryField::RequiredCoin, &category)?;
+        vote.required_coins = pick(update.required_coin.clone(), vote.required_coins, CategoryField::RequiredCoin, &category)?;
+        vote.total_power = total_power(&vote.threshold, vote.whitelist_on, &vote.whitelist)?;
+    }
+    if let Some(description) = &update.description {
+        assert_valid_description(description)?;
+        vote.description = description.clone();
+    }
+    if let Some(links) = &update.links {
+        assert_valid_links(links)?;
+        vote.links = links.clone();
+    }
+    if let Some(tags) = &update.tags {
+        assert_valid_tags(tags)?;
+        remove_tags(deps.storage, &title, &vote.tags);
+        store_tags(deps.storage, &title, tags)?;
+        vote.tags = tags.clone();
+    }
+    vote.revision += 1;
+    let revision = ProposalRevision {
+        revision: vote.revision,
+        editor: info.sender.clone(),
+        height: env.block.height,
+        update,
+    };
+    store_revision(deps.storage, &title, &revision)?;
+    store_vote(deps.storage, &title, vote)?;
+
+    let event = GovernanceEvent::ProposalUpdated { title, editor: info.sender, revision: revision.revision };
+    Ok(Response::new()
+        .add_attribute("action", "execute update proposal")
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_delegate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    to: Addr
+) -> Result<Response, ContractError> {
+    if to == info.sender {
+        return Err(ContractError::CannotDelegateToSelf {});
+    }
+    let mut current = to.clone();
+    let mut depth = 1;
+    while let Some(next) = may_load_delegation(deps.storage, &current)? {
+        if next == info.sender {
+            return Err(ContractError::DelegationCycle {});
+        }
+        depth += 1;
+        if depth >= MAX_DELEGATION_DEPTH {
+            return Err(ContractError::DelegationTooDeep {});
+        }
+        current = next;
+    }
+    // the delegators of the sender move down with it
+    if depth + subtree_height(deps.storage, &info.sender)? >= MAX_DELEGATION_DEPTH {
+        return Err(ContractError::DelegationTooDeep {});
+    }
+    // moving inside the same tree does not change its size
+    if current != delegation_root(deps.storage, &info.sender)?
+        && subtree_size(deps.storage, &current)? + subtree_size(deps.storage, &info.sender)? > MAX_DELEGATION_TREE {
+        return Err(ContractError::DelegationTreeTooLarge {});
+    }
+    if let Some(previous) = may_load_delegation(deps.storage, &info.sender)? {
+        remove_delegation(deps.storage, &info.sender, &previous);
+    }
+    store_delegation(deps.storage, &info.sender, &to)?;
+    let event = GovernanceEvent::DelegationSet { delegator: info.sender, delegate: to.clone() };
+    Ok(Response::new()
+        .add_attribute("action", "execute delegate")
+        .add_attribute("to", to)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_undelegate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let delegate = may_load_delegation(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotDelegating {})?;
+    remove_delegation(deps.storage, &info.sender, &delegate);
+    let event = GovernanceEvent::DelegationRemoved { delegator: info.sender, delegate };
+    Ok(Response::new()
+        .add_attribute("action", "execute undelegate")
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_set_category(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String,
+    category: ProposalCategory
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    assert_valid_threshold(&category.threshold, category.veto_threshold)?;
+    assert_valid_pause_cap(&category.voting_period, &category.max_pause_extension)?;
+    assert_valid_late_quorum(&category.voting_period, &category.late_quorum)?;
+    store_category(deps.storage, &name, &category)?;
+    let event = GovernanceEvent::CategorySet { name: name.clone() };
+    Ok(Response::new()
+        .add_attribute("action", "execute set category")
+        .add_attribute("name", name)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_remove_category(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    name: String
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    may_load_category(deps.storage, &name)?
+        .ok_or(ContractError::CannotFindCategory {})?;
+    remove_category(deps.storage, &name);
+    let event = GovernanceEvent::CategoryRemoved { name: name.clone() };
+    Ok(Response::new()
+        .add_attribute("action", "execute remove category")
+        .add_attribute("name", name)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_recompute_stats(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    let limit = limit.unwrap_or(DEFAULT_RECOMPUTE_LIMIT).min(MAX_RECOMPUTE_LIMIT) as usize;
+    let mut progress = match may_load_recompute(deps.storage)? {
+        Some(progress) => progress,
+        None => {
+            // creator stats are rebuilt in place along with Stats
+            clear_creator_stats(deps.storage)?;
+            RecomputeProgress {
+                cursor: None,
+                stats: Stats::default(),
+            }
+        }
+    };
+    let min = progress.cursor.clone().map(Bound::exclusive);
+    let votes = range_votes(deps.storage, min, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let done = votes.len() <= limit;
+    let processed = votes.len().min(limit);
+    for (title, vote) in votes.into_iter().take(limit) {
+        accumulate(&mut progress.stats, &vote)?;
+        accumulate_creator(deps.storage, &vote)?;
+        progress.cursor = Some(title);
+    }
+    if done {
+        let mismatches = stats_mismatches(&read_stats(deps.storage)?, &progress.stats);
+        store_last_recompute(deps.storage, &RecomputeReport { height: env.block.height, mismatches })?;
+        store_stats(deps.storage, &progress.stats)?;
+        remove_recompute(deps.storage);
+    } else {
+        store_recompute(deps.storage, &progress)?;
+    }
+    let event = GovernanceEvent::StatsRecomputed { processed: processed as u64, done };
+    Ok(Response::new()
+        .add_attribute("action", "execute recompute stats")
+        .add_attribute("processed", processed.to_string())
+        .add_attribute("done", done.to_string())
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_add_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    addr: Addr,
+    policy: HookPolicy
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    if may_load_hook(deps.storage, &addr)?.is_some() {
+        return Err(ContractError::HookAlreadyRegistered {});
+    }
+    store_hook(deps.storage, &addr, &policy)?;
+    let event = GovernanceEvent::HookAdded { addr: addr.clone(), policy };
+    Ok(Response::new()
+        .add_attribute("action", "execute add hook")
+        .add_attribute("hook", addr)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_remove_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    addr: Addr
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    may_load_hook(deps.storage, &addr)?
+        .ok_or(ContractError::HookNotRegistered {})?;
+    remove_hook(deps.storage, &addr);
+    let event = GovernanceEvent::HookRemoved { addr: addr.clone() };
+    Ok(Response::new()
+        .add_attribute("action", "execute remove hook")
+        .add_attribute("hook", addr)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_set_reward_pool(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset: RewardAsset,
+    per_proposal: Uint128
+) -> Result<Response, ContractError> {
+    if is_owner(deps.storage, info.sender.clone()) != Ok(true)
+        && is_admin(deps.storage, info.sender.clone()) != Ok(true) {
+        return Err(ContractError::SenderIsNotAdmin {});
+    }
+    let pool = match may_load_reward_pool(deps.storage)? {
+        // funds of the current asset must be used up before switching to another one
+        Some(pool) if pool.asset != asset && !(pool.balance.is_zero() && pool.pending.is_zero()) => {
+            return Err(ContractError::RewardAssetLocked {});
+        }
+        Some(pool) => RewardPool { asset, per_proposal, ..pool },
+        None => RewardPool {
+            asset,
+            per_proposal,
+            balance: Uint128::zero(),
+            pending: Uint128::zero(),
+        },
+    };
+    store_reward_pool(deps.storage, &pool)?;
+    let event = GovernanceEvent::RewardPoolSet { asset: pool.asset, per_proposal };
+    Ok(Response::new()
+        .add_attribute("action", "execute set reward pool")
+        .add_attribute("per_proposal", per_proposal)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_fund_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let pool = may_load_reward_pool(deps.storage)?
+        .ok_or(ContractError::RewardPoolNotConfigured {})?;
+    let amount = match &pool.asset {
+        RewardAsset::Native { denom } => info.funds.iter()
+            .find(|x| x.denom == *denom)
+            .map(|x| x.amount)
+            .unwrap_or_default(),
+        RewardAsset::Cw20 { .. } => return Err(ContractError::InvalidRewardFunds {}),
+    };
+    fund_rewards(deps, pool, info.sender, amount)
+}
+pub fn execute_receive(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg
+) -> Result<Response, ContractError> {
+    let pool = may_load_reward_pool(deps.storage)?
+        .ok_or(ContractError::RewardPoolNotConfigured {})?;
+    // only the configured token contract can fund the pool
+    match &pool.asset {
+        RewardAsset::Cw20 { contract_addr } if *contract_addr == info.sender => {}
+        _ => return Err(ContractError::InvalidRewardFunds {}),
+    }
+    match from_binary(&wrapper.msg)? {
+        ReceiveMsg::FundRewards {} => fund_rewards(deps, pool, Addr::unchecked(wrapper.sender), wrapper.amount),
+    }
+}
+fn fund_rewards(deps: DepsMut, mut pool: RewardPool, sender: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidRewardFunds {});
+    }
+    pool.balance = pool.balance.checked_add(amount)?;
+    store_reward_pool(deps.storage, &pool)?;
+    let event = GovernanceEvent::RewardsFunded { sender, amount };
+    Ok(Response::new()
+        .add_attribute("action", "execute fund rewards")
+        .add_attribute("amount", amount)
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_claim_rewards(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> Result<Response, ContractError> {
+    let mut pool = may_load_reward_pool(deps.storage)?
+        .ok_or(ContractError::RewardPoolNotConfigured {})?;
+    let limit = limit.unwrap_or(DEFAULT_CLAIM_LIMIT).min(MAX_CLAIM_LIMIT) as usize;
+    let claimable = claimable_rewards(deps.storage, &info.sender, start_after, limit)?;
+    // ballots worth less than a unit are still claimed, so the dust of their proposals is released
+    if claimable.ballots.is_empty() {
+        return Err(ContractError::NoRewardsToClaim {});
+    }
+    settle_claim(deps.storage, &mut pool, &info.sender, &claimable)?;
+    store_reward_pool(deps.storage, &pool)?;
+    let amount = claimable.amount;
+    let mut response = Response::new()
+        .add_attribute("action", "execute claim rewards")
+        .add_attribute("amount", amount)
+        .add_attribute("last_title", claimable.last_title.unwrap_or_default());
+    if !amount.is_zero() {
+        response = response.add_message(reward_msg(&pool.asset, &info.sender, amount)?);
+    }
+    let event = GovernanceEvent::RewardsClaimed { voter: info.sender, amount };
+    Ok(response.add_event(event.to_event()))
+}
+pub fn execute_set_guardian(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    guardian: Option<Addr>,
+    unpause_delay: Option<Duration>
+) -> Result<Response, ContractError> {
+    if !is_owner(deps.storage, info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    update_config(deps.storage, |mut config| -> Result<_, ContractError> {
+        config.guardian = guardian.clone();
+        config.unpause_delay = unpause_delay;
+        Ok(config)
+    })?;
+    let event = GovernanceEvent::GuardianSet { guardian: guardian.clone(), unpause_delay };
+    Ok(Response::new()
+        .add_attribute("action", "execute set guardian")
+        .add_attribute("guardian", guardian.map_or_else(String::new, String::from))
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_emergency_pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.owner != info.sender && config.guardian.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    // proposals keep their own paused flags, so Stats stay as they are
+    update_config(deps.storage, |mut config| -> Result<_, ContractError> {
+        config.emergency_pause = Some(EmergencyPauseState {
+            paused_by: info.sender.clone(),
+            unpause_after: config.unpause_delay.map(|delay| delay.after(&env.block)),
+            paused_at_height: env.block.height,
+            paused_at_time: env.block.time,
+        });
+        Ok(config)
+    })?;
+    let event = GovernanceEvent::EmergencyPaused { sender: info.sender };
+    Ok(Response::new()
+        .add_attribute("action", "execute emergency pause")
+        .add_event(event.to_event())
+    )
+}
+pub fn execute_emergency_unpause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let pause = config.emergency_pause.ok_or(ContractError::NotEmergencyPaused {})?;
+    if let Some(unpause_after) = pause.unpause_after {
+        if !unpause_after.is_expired(&env.block) {
+            return Err(ContractError::UnpauseDelayNotElapsed {});
+        }
+    }
+    update_config(deps.storage, |mut config| -> Result<_, ContractError> {
+        config.emergency_pause = None;
+        Ok(config)
+    })?;
+    // open proposals get back the blocks or seconds the contract spent paused the next time
+    // they are read, see apply_emergency_pauses
+    store_emergency_pause(
+        deps.storage,
+        BlockStamp { height: pause.paused_at_height, time: pause.paused_at_time },
+        env.block.height.saturating_sub(pause.paused_at_height),
+        env.block.time.seconds().saturating_sub(pause.paused_at_time.seconds()),
+    )?;
+    let event = GovernanceEvent::EmergencyUnpaused { sender: info.sender };
+    Ok(Response::new()
+        .add_attribute("action", "execute emergency unpause")
+        .add_event(event.to_event())
+    )
+}
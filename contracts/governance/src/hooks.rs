@@ -0,0 +1,20 @@
+use cosmwasm_std::{Storage, StdResult, SubMsg};
+use governance_types::hooks::{GovernanceHookMsg, HookPolicy};
+use crate::state::load_hooks;
+
+// Reply id of the hooks registered with HookPolicy::Ignore
+pub const HOOK_REPLY_ID: u64 = 1;
+
+// One message per registered hook, only `Ignore` hooks get a reply on error.
+pub fn prepare_hooks (storage: &dyn Storage, msg: GovernanceHookMsg) -> StdResult<Vec<SubMsg>> {
+    load_hooks(storage)?
+        .into_iter()
+        .map(|(addr, policy)| -> StdResult<SubMsg> {
+            let execute = msg.clone().into_cosmos_msg(addr)?;
+            Ok(match policy {
+                HookPolicy::Revert => SubMsg::new(execute),
+                HookPolicy::Ignore => SubMsg::reply_on_error(execute, HOOK_REPLY_ID),
+            })
+        })
+        .collect()
+}
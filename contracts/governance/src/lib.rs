@@ -5,3 +5,8 @@ pub mod queries;
 pub mod test;
 pub mod assert;
 pub mod stats;
+pub mod tally;
+pub mod delegation;
+pub mod migrations;
+pub mod hooks;
+pub mod rewards;
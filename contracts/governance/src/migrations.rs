@@ -0,0 +1,205 @@
+use cosmwasm_std::{Addr, Coin, Decimal, Order, StdError, StdResult, Storage, Uint128, Uint64};
+use cw0::Expiration;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use governance_types::errors::ContractError;
+use governance_types::types::{RefundPolicy, Threshold};
+use governance_types::types::MigrateMsg;
+use semver::Version;
+use crate::state::{Ballot, Stats, VoteStatus, store_ballot, store_stats, store_vote, range_votes, load_vote, may_load_ballot};
+use crate::stats::accumulate;
+
+// Runs every migration needed to go from the `from` version to the current one, in order.
+// `from` is None for contracts deployed before cw2 versioning.
+pub fn migrate_from(storage: &mut dyn Storage, from: Option<Version>, msg: &MigrateMsg) -> Result<(), ContractError> {
+    // deployments without a cw2 version still store the i32 layout of the first release
+    if from.is_none() {
+        migrate_counters_to_uint(storage)?;
+    }
+    let from = from.unwrap_or_else(|| Version::new(0, 0, 0));
+    if from < Version::new(0, 0, 2) {
+        migrate_participants_to_ballots(storage)?;
+    }
+    if from < Version::new(0, 0, 3) {
+        index_votes(storage)?;
+    }
+    if msg.rebuild_stats {
+        rebuild_stats(storage)?;
+    }
+    Ok(())
+}
+
+fn rebuild_stats(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let mut stats = Stats::default();
+    for item in range_votes(storage, None, None, Order::Ascending) {
+        let (_, vote) = item?;
+        accumulate(&mut stats, &vote)?;
+    }
+    store_stats(storage, &stats)?;
+    Ok(())
+}
+
+// Layouts of the first release, stored with i32 counters and before categories, thresholds
+// and ballots existed. They read the same keys as the current items in state.rs.
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct LegacyStats {
+    in_progress: i32,
+    rejected_votes: i32,
+    accepted: i32,
+    not_resolved: i32,
+    paused: i32,
+}
+const LEGACY_STATS: Item<LegacyStats> = Item::new("\u{0}\u{6}stats");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct LegacyVoteStatus {
+    paused: bool,
+    votes_for: i32,
+    votes_against: i32,
+    votes_abstain: i32,
+    creator: Addr,
+    required_coins_on: bool,
+    required_coins: Coin,
+    min_votes_count: i32,
+    required_votes_percentage: i32,
+    already_participate: Vec<Addr>,
+    whitelist_on: bool,
+    whitelist: Vec<Addr>,
+}
+const LEGACY_VOTES: Map<&str, LegacyVoteStatus> = Map::new("VOTES");
+
+// Category name given to the proposals created before categories existed.
+pub const LEGACY_CATEGORY: &str = "legacy";
+// Veto share of the migrated proposals, the first release had no NoWithVeto option.
+fn legacy_veto_threshold() -> Decimal {
+    Decimal::percent(34)
+}
+
+// Negative counters cannot be represented anymore, they are clamped to zero.
+fn to_uint64(value: i32) -> Uint64 {
+    Uint64::new(value.max(0) as u64)
+}
+fn to_uint128(value: i32) -> Uint128 {
+    Uint128::new(value.max(0) as u128)
+}
+
+// The required percentage of For votes becomes an absolute percentage threshold,
+// a zero percentage only asked for a single For vote.
+fn legacy_threshold(required_votes_percentage: i32) -> Threshold {
+    match required_votes_percentage {
+        percentage if percentage <= 0 => Threshold::AbsoluteCount { weight: 1 },
+        percentage => Threshold::AbsolutePercentage { percentage: Decimal::percent(percentage.min(100) as u64) },
+    }
+}
+
+pub fn migrate_counters_to_uint(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let stats = LEGACY_STATS.load(storage)?;
+    store_stats(storage, &Stats {
+        in_progress: to_uint64(stats.in_progress),
+        rejected_votes: to_uint64(stats.rejected_votes),
+        accepted: to_uint64(stats.accepted),
+        not_resolved: to_uint64(stats.not_resolved),
+        paused: to_uint64(stats.paused),
+        ..Stats::default()
+    })?;
+
+    let votes = LEGACY_VOTES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (key, vote) in votes {
+        let title = String::from_utf8(key).map_err(StdError::from)?;
+        // the indexed map cannot read the legacy value to clean its indexes, none exist yet
+        LEGACY_VOTES.remove(storage, &title);
+        // whitelisted votes are one address one vote, as for new proposals
+        let total_power = if vote.whitelist_on { Some(vote.whitelist.len() as u64) } else { None };
+        store_vote(storage, &title, VoteStatus {
+            paused: vote.paused,
+            votes_for: to_uint128(vote.votes_for),
+            votes_against: to_uint128(vote.votes_against),
+            votes_abstain: to_uint128(vote.votes_abstain),
+            votes_no_with_veto: Uint128::zero(),
+            creator: vote.creator,
+            required_coins_on: vote.required_coins_on,
+            required_coins: vote.required_coins,
+            min_votes_count: to_uint128(vote.min_votes_count),
+            threshold: legacy_threshold(vote.required_votes_percentage),
+            veto_threshold: legacy_veto_threshold(),
+            total_power,
+            ballots: Uint64::new(vote.already_participate.len() as u64),
+            whitelist_on: vote.whitelist_on,
+            whitelist: vote.whitelist,
+            outcome: None,
+            category: LEGACY_CATEGORY.to_string(),
+            expires: Expiration::Never {},
+            deposit: None,
+            cancel_refund: RefundPolicy::default(),
+            cancel_reason: None,
+            description: String::new(),
+            links: vec![],
+            tags: vec![],
+            revision: 0,
+            original_expires: None,
+            max_pause_extension: None,
+            pause_extension: 0,
+            paused_at: None,
+            late_quorum: None,
+            quorum_reached_at: None,
+            emergency_pauses_seen: 0,
+        })?;
+        store_placeholder_ballots(storage, &title, &vote.already_participate)?;
+    }
+    Ok(())
+}
+
+// The choice of voters from before ballots were recorded is unknown, an empty
+// ballot keeps them from voting twice.
+fn store_placeholder_ballots(storage: &mut dyn Storage, title: &str, voters: &[Addr]) -> Result<(), ContractError> {
+    for voter in voters {
+        if may_load_ballot(storage, title, voter)?.is_none() {
+            store_ballot(storage, title, voter, &Ballot {
+                vote: String::new(),
+                power: Uint128::zero(),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+// Voters stored inside VoteStatus before 0.0.2, read from the same key as VOTES.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct LegacyParticipants {
+    #[serde(default)]
+    already_participate: Option<Vec<Addr>>,
+}
+const LEGACY_PARTICIPANTS: Map<&str, LegacyParticipants> = Map::new("VOTES");
+
+pub fn migrate_participants_to_ballots(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let votes = LEGACY_PARTICIPANTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (key, participants) in votes {
+        let title = String::from_utf8(key).map_err(StdError::from)?;
+        let participants = match participants.already_participate {
+            Some(participants) => participants,
+            None => continue,
+        };
+        store_placeholder_ballots(storage, &title, &participants)?;
+        // rewriting the vote drops the inline voters list
+        let mut vote = load_vote(storage, &title)?;
+        vote.ballots = Uint64::new(participants.len() as u64);
+        store_vote(storage, &title, vote)?;
+    }
+    Ok(())
+}
+
+// Saves every vote again to fill the status, creator and end indexes added in 0.0.3.
+pub fn index_votes(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    let votes = range_votes(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (title, vote) in votes {
+        store_vote(storage, &title, vote)?;
+    }
+    Ok(())
+}
@@ -1,32 +1,494 @@
-use cosmwasm_std::Deps;
+use cosmwasm_std::{Addr, Decimal, Deps, Env, Order, StdResult, Uint128, Uint64};
+use cw_storage_plus::{Bound, U64Key};
 use governance_types::errors::ContractError;
-use crate::state::{Config, VoteStatus, Stats, read_config, may_load_vote, read_stats};
+use governance_types::types::{DelegatorsResponse, ProposalCategory, ProposalListResponse, ProposalStatus, ProposalSummary,
+    BallotInfo, BallotResponse, BallotListResponse, ConfigResponse, ProposalResponse, StatsResponse,
+    TallyResponse, ExtendedStatsResponse, CreatorStatsResponse, ProposalBreakdownResponse,
+    VerifyStatsResponse, StatsSnapshot, StatsHistoryResponse, HookInfo, HooksResponse,
+    RewardPoolResponse, PendingRewardsResponse, ProposalRevisionsResponse};
+use crate::state::{Stats, VoteStatus, read_config, may_load_vote, load_vote, read_stats, may_load_delegation, load_delegators,
+    may_load_category, load_category_names, range_votes, range_votes_by_status, range_votes_by_creator, range_votes_by_status_and_creator, may_load_ballot, range_ballots_by_title,
+    range_ballots_by_voter, ballot_key, may_load_creator_stats, may_load_stats_at, range_stats_history,
+    load_hooks, load_vote_titles, may_load_reward_pool, may_load_last_recompute, range_revisions,
+    range_titles_by_tag};
+use crate::delegation::{delegated_power, MAX_DELEGATION_DEPTH};
+use crate::tally::{compute_outcome, threshold_share, remaining_period, apply_emergency_pauses};
+use crate::stats::turnout;
+use crate::rewards::claimable_rewards;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 pub fn query_config(
     deps: Deps,
-) -> Result<Config, ContractError> {
+) -> Result<ConfigResponse, ContractError> {
     let config = read_config(deps.storage)?;
-    Ok(config)
+    Ok(ConfigResponse {
+        owner: config.owner,
+        admins: config.admins,
+        guardian: config.guardian,
+        unpause_delay: config.unpause_delay,
+        emergency_pause: config.emergency_pause,
+    })
 }
 
 pub fn query_get_vote(
     deps: Deps,
     title: String
-) -> Result<Option<VoteStatus>, ContractError> {
-    let vote_status = may_load_vote(deps.storage, &title)?;
-    Ok(vote_status)
+) -> Result<Option<ProposalResponse>, ContractError> {
+    let vote_status = may_load_current_vote(deps, &title)?;
+    Ok(vote_status.map(|vote| ProposalResponse {
+        title,
+        status: vote.status(),
+        creator: vote.creator,
+        category: vote.category,
+        votes_for: vote.votes_for,
+        votes_against: vote.votes_against,
+        votes_abstain: vote.votes_abstain,
+        votes_no_with_veto: vote.votes_no_with_veto,
+        min_votes_count: vote.min_votes_count,
+        threshold: vote.threshold,
+        veto_threshold: vote.veto_threshold,
+        total_power: vote.total_power,
+        whitelist_on: vote.whitelist_on,
+        whitelist: vote.whitelist,
+        required_coins_on: vote.required_coins_on,
+        required_coins: vote.required_coins,
+        deposit: vote.deposit,
+        original_expires: vote.original_expires.unwrap_or(vote.expires),
+        expires: vote.expires,
+        outcome: vote.outcome,
+        cancel_reason: vote.cancel_reason,
+        quorum_reached_height: vote.quorum_reached_at.map(|reached| reached.height),
+        description: vote.description,
+        links: vote.links,
+        tags: vote.tags,
+        revision: vote.revision,
+    }))
 }
 
 pub fn query_get_votes_titles(
     deps: Deps,
 ) -> Result<Vec<String>, ContractError> {
-    let config = read_config(deps.storage)?;
-    Ok(config.votes_titles)
+    Ok(load_vote_titles(deps.storage)?)
 }
 
 pub fn query_get_stats(
     deps: Deps,
-) -> Result<Stats, ContractError> {
+) -> Result<StatsResponse, ContractError> {
     let stats = read_stats(deps.storage)?;
-    Ok(stats)
-}
\ No newline at end of file
+    Ok(stats_response(&stats))
+}
+
+fn stats_response(stats: &Stats) -> StatsResponse {
+    StatsResponse {
+        in_progress: stats.in_progress,
+        rejected_votes: stats.rejected_votes,
+        accepted: stats.accepted,
+        not_resolved: stats.not_resolved,
+        paused: stats.paused,
+        vetoed: stats.vetoed,
+        cancelled: stats.cancelled,
+    }
+}
+
+pub fn query_stats_at(
+    deps: Deps,
+    height: u64
+) -> Result<Option<StatsSnapshot>, ContractError> {
+    let snapshot = may_load_stats_at(deps.storage, height)?
+        .map(|(height, stats)| StatsSnapshot {
+            height,
+            stats: stats_response(&stats),
+        });
+    Ok(snapshot)
+}
+
+pub fn query_stats_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>
+) -> Result<StatsHistoryResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(|height| Bound::exclusive(U64Key::new(height)));
+    let snapshots = range_stats_history(deps.storage, min, None, Order::Ascending)
+        .take(limit)
+        .map(|item| -> StdResult<StatsSnapshot> {
+            let (height, stats) = item?;
+            Ok(StatsSnapshot {
+                height,
+                stats: stats_response(&stats),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(StatsHistoryResponse { snapshots })
+}
+
+pub fn query_get_delegation(
+    deps: Deps,
+    address: Addr
+) -> Result<Option<Addr>, ContractError> {
+    let delegate = may_load_delegation(deps.storage, &address)?;
+    Ok(delegate)
+}
+
+pub fn query_get_delegators(
+    deps: Deps,
+    delegate: Addr
+) -> Result<DelegatorsResponse, ContractError> {
+    let delegators = load_delegators(deps.storage, &delegate)?;
+    let total_power = delegated_power(deps.storage, &delegate, MAX_DELEGATION_DEPTH)?;
+    Ok(DelegatorsResponse {
+        delegate,
+        delegators,
+        total_power,
+    })
+}
+
+pub fn query_get_category(
+    deps: Deps,
+    name: String
+) -> Result<Option<ProposalCategory>, ContractError> {
+    let category = may_load_category(deps.storage, &name)?;
+    Ok(category)
+}
+
+pub fn query_get_categories(
+    deps: Deps,
+) -> Result<Vec<String>, ContractError> {
+    let names = load_category_names(deps.storage)?;
+    Ok(names)
+}
+
+pub fn query_list_proposals(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    status: Option<ProposalStatus>,
+    creator: Option<Addr>
+) -> Result<ProposalListResponse, ContractError> {
+    let min = start_after.map(Bound::exclusive);
+    list_proposals(deps, min, None, Order::Ascending, limit, status, creator)
+}
+
+pub fn query_reverse_proposals(
+    deps: Deps,
+    start_before: Option<String>,
+    limit: Option<u32>,
+    status: Option<ProposalStatus>,
+    creator: Option<Addr>
+) -> Result<ProposalListResponse, ContractError> {
+    let max = start_before.map(Bound::exclusive);
+    list_proposals(deps, None, max, Order::Descending, limit, status, creator)
+}
+
+fn list_proposals(
+    deps: Deps,
+    min: Option<Bound>,
+    max: Option<Bound>,
+    order: Order,
+    limit: Option<u32>,
+    status: Option<ProposalStatus>,
+    creator: Option<Addr>
+) -> Result<ProposalListResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let votes = match (&status, &creator) {
+        (Some(status), Some(creator)) => range_votes_by_status_and_creator(deps.storage, status, creator, min, max, order),
+        (Some(status), None) => range_votes_by_status(deps.storage, status, min, max, order),
+        (None, Some(creator)) => range_votes_by_creator(deps.storage, creator, min, max, order),
+        (None, None) => range_votes(deps.storage, min, max, order),
+    };
+    let proposals = votes
+        .take(limit)
+        .map(|item| -> Result<ProposalSummary, ContractError> {
+            let (title, mut vote) = item?;
+            apply_emergency_pauses(deps.storage, &mut vote)?;
+            Ok(proposal_summary(title, vote))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    Ok(ProposalListResponse { proposals })
+}
+
+pub fn query_proposals_by_tag(
+    deps: Deps,
+    tag: String,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> Result<ProposalListResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(Bound::exclusive);
+    let proposals = range_titles_by_tag(deps.storage, &tag, min, Order::Ascending)
+        .take(limit)
+        .map(|item| -> Result<ProposalSummary, ContractError> {
+            let title = item?;
+            let mut vote = load_vote(deps.storage, &title)?;
+            apply_emergency_pauses(deps.storage, &mut vote)?;
+            Ok(proposal_summary(title, vote))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    Ok(ProposalListResponse { proposals })
+}
+
+// The vote as the next execute would see it, with the emergency pauses it has not seen yet.
+fn may_load_current_vote(deps: Deps, title: &str) -> Result<Option<VoteStatus>, ContractError> {
+    match may_load_vote(deps.storage, title)? {
+        Some(mut vote) => {
+            apply_emergency_pauses(deps.storage, &mut vote)?;
+            Ok(Some(vote))
+        }
+        None => Ok(None),
+    }
+}
+
+fn proposal_summary(title: String, vote: VoteStatus) -> ProposalSummary {
+    ProposalSummary {
+        title,
+        status: vote.status(),
+        creator: vote.creator,
+        votes_for: vote.votes_for,
+        votes_against: vote.votes_against,
+        votes_abstain: vote.votes_abstain,
+        votes_no_with_veto: vote.votes_no_with_veto,
+        expires: vote.expires,
+        tags: vote.tags,
+    }
+}
+
+pub fn query_get_ballot(
+    deps: Deps,
+    title: String,
+    voter: Addr
+) -> Result<BallotResponse, ContractError> {
+    let ballot = may_load_ballot(deps.storage, &title, &voter)?.map(|ballot| BallotInfo {
+        title,
+        voter,
+        vote: ballot.vote,
+        power: ballot.power,
+    });
+    Ok(BallotResponse { ballot })
+}
+
+pub fn query_list_votes(
+    deps: Deps,
+    title: String,
+    start_after: Option<Addr>,
+    limit: Option<u32>
+) -> Result<BallotListResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(|voter| Bound::exclusive(voter.as_bytes()));
+    let ballots = range_ballots_by_title(deps.storage, &title, min, Order::Ascending)
+        .take(limit)
+        .map(|item| -> StdResult<BallotInfo> {
+            let (voter, ballot) = item?;
+            Ok(BallotInfo {
+                title: title.clone(),
+                voter,
+                vote: ballot.vote,
+                power: ballot.power,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(BallotListResponse { ballots })
+}
+
+pub fn query_voter_history(
+    deps: Deps,
+    voter: Addr,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> Result<BallotListResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(|title| Bound::exclusive(ballot_key(&title, &voter)));
+    let ballots = range_ballots_by_voter(deps.storage, &voter, min, Order::Ascending)
+        .take(limit)
+        .map(|item| -> StdResult<BallotInfo> {
+            let (title, ballot) = item?;
+            Ok(BallotInfo {
+                title,
+                voter: voter.clone(),
+                vote: ballot.vote,
+                power: ballot.power,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(BallotListResponse { ballots })
+}
+
+pub fn query_tally(
+    deps: Deps,
+    env: Env,
+    title: String
+) -> Result<TallyResponse, ContractError> {
+    let vote = may_load_current_vote(deps, &title)?
+        .ok_or(ContractError::CannotFindVote {})?;
+    let decisive = vote.votes_for
+        .checked_add(vote.votes_against)?
+        .checked_add(vote.votes_no_with_veto)?;
+    let total_votes = decisive.checked_add(vote.votes_abstain)?;
+    let quorum_reached = vote.total_power
+        .filter(|total_power| *total_power > 0)
+        .map(|total_power| Decimal::from_ratio(total_votes, Uint128::from(total_power)));
+    let projected_outcome = match (&vote.outcome, &vote.cancel_reason) {
+        (Some(outcome), _) => Some(outcome.clone()),
+        (None, Some(_)) => None,
+        (None, None) => Some(compute_outcome(&vote)?),
+    };
+    Ok(TallyResponse {
+        title,
+        votes_for: vote.votes_for,
+        votes_against: vote.votes_against,
+        votes_abstain: vote.votes_abstain,
+        votes_no_with_veto: vote.votes_no_with_veto,
+        total_votes,
+        total_power: vote.total_power,
+        quorum_reached,
+        threshold_reached: threshold_share(&vote)?,
+        remaining: remaining_period(&vote.expires, &env.block),
+        projected_outcome,
+    })
+}
+
+// Ratio which is zero instead of panicking on an empty denominator.
+fn ratio(numerator: impl Into<Uint128>, denominator: impl Into<Uint128>) -> Decimal {
+    let denominator = denominator.into();
+    if denominator.is_zero() {
+        return Decimal::zero();
+    }
+    Decimal::from_ratio(numerator, denominator)
+}
+
+pub fn query_get_extended_stats(
+    deps: Deps,
+) -> Result<ExtendedStatsResponse, ContractError> {
+    let stats = read_stats(deps.storage)?;
+    let finalized = stats.accepted
+        .checked_add(stats.rejected_votes)?
+        .checked_add(stats.not_resolved)?
+        .checked_add(stats.vetoed)?;
+    let participation_rate = if stats.measured_power.is_zero() {
+        None
+    } else {
+        Some(ratio(stats.measured_turnout, stats.measured_power))
+    };
+    Ok(ExtendedStatsResponse {
+        votes_for: stats.votes_for,
+        votes_against: stats.votes_against,
+        votes_abstain: stats.votes_abstain,
+        votes_no_with_veto: stats.votes_no_with_veto,
+        ballots: stats.ballots,
+        finalized,
+        average_turnout: ratio(stats.finalized_turnout, finalized.u64()),
+        participation_rate,
+    })
+}
+
+pub fn query_get_creator_stats(
+    deps: Deps,
+    creator: Addr
+) -> Result<CreatorStatsResponse, ContractError> {
+    let stats = may_load_creator_stats(deps.storage, &creator)?.unwrap_or_default();
+    let finalized: Uint64 = stats.accepted
+        .checked_add(stats.rejected)?
+        .checked_add(stats.not_resolved)?
+        .checked_add(stats.vetoed)?;
+    Ok(CreatorStatsResponse {
+        creator,
+        created: stats.created,
+        accepted: stats.accepted,
+        rejected: stats.rejected,
+        not_resolved: stats.not_resolved,
+        vetoed: stats.vetoed,
+        cancelled: stats.cancelled,
+        average_turnout: ratio(stats.finalized_turnout, finalized.u64()),
+    })
+}
+
+pub fn query_get_proposal_breakdown(
+    deps: Deps,
+    title: String
+) -> Result<ProposalBreakdownResponse, ContractError> {
+    let vote = may_load_vote(deps.storage, &title)?
+        .ok_or(ContractError::CannotFindVote {})?;
+    let total_votes = turnout(&vote)?;
+    let ballots = vote.ballots.u64();
+    Ok(ProposalBreakdownResponse {
+        title,
+        total_votes,
+        for_share: ratio(vote.votes_for, total_votes),
+        against_share: ratio(vote.votes_against, total_votes),
+        abstain_share: ratio(vote.votes_abstain, total_votes),
+        no_with_veto_share: ratio(vote.votes_no_with_veto, total_votes),
+        ballots,
+        delegated: total_votes.saturating_sub(Uint128::from(ballots)),
+        participation_rate: vote.total_power
+            .filter(|total_power| *total_power > 0)
+            .map(|total_power| ratio(total_votes, total_power)),
+    })
+}
+
+pub fn query_verify_stats(
+    deps: Deps,
+) -> Result<VerifyStatsResponse, ContractError> {
+    let report = may_load_last_recompute(deps.storage)?;
+    let height = report.as_ref().map(|report| report.height);
+    let mismatches = report.map(|report| report.mismatches).unwrap_or_default();
+    Ok(VerifyStatsResponse {
+        height,
+        consistent: mismatches.is_empty(),
+        mismatches,
+    })
+}
+
+pub fn query_hooks(
+    deps: Deps,
+) -> Result<HooksResponse, ContractError> {
+    let hooks = load_hooks(deps.storage)?
+        .into_iter()
+        .map(|(addr, policy)| HookInfo { addr, policy })
+        .collect();
+    Ok(HooksResponse { hooks })
+}
+
+pub fn query_reward_pool(
+    deps: Deps,
+) -> Result<Option<RewardPoolResponse>, ContractError> {
+    let pool = may_load_reward_pool(deps.storage)?
+        .map(|pool| RewardPoolResponse {
+            asset: pool.asset,
+            per_proposal: pool.per_proposal,
+            balance: pool.balance,
+            pending: pool.pending,
+        });
+    Ok(pool)
+}
+
+pub fn query_pending_rewards(
+    deps: Deps,
+    address: Addr,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> Result<PendingRewardsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let asset = may_load_reward_pool(deps.storage)?.map(|pool| pool.asset);
+    let claimable = claimable_rewards(deps.storage, &address, start_after, limit)?;
+    Ok(PendingRewardsResponse {
+        address,
+        asset,
+        amount: claimable.amount,
+        last_title: claimable.last_title,
+    })
+}
+
+pub fn query_proposal_revisions(
+    deps: Deps,
+    title: String,
+    start_after: Option<u64>,
+    limit: Option<u32>
+) -> Result<ProposalRevisionsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min = start_after.map(|revision| Bound::exclusive(U64Key::new(revision)));
+    let revisions = range_revisions(deps.storage, &title, min, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProposalRevisionsResponse { revisions })
+}
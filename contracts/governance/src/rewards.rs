@@ -0,0 +1,101 @@
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Order, StdResult, Storage, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use governance_types::errors::ContractError;
+use governance_types::types::RewardAsset;
+use cw_storage_plus::Bound;
+use crate::state::{VoteStatus, RewardPool, ProposalReward, range_ballots_by_voter, ballot_key, may_load_reward_pool, store_reward_pool,
+    may_load_proposal_reward, store_proposal_reward, is_reward_claimed, store_reward_claimed};
+use crate::stats::turnout;
+
+// Sets aside the proposal allotment of the reward pool and records what each unit of
+// power cast on it earns. Voters claim their share later, rounding dust goes back to the
+// balance once every ballot was claimed. Returns the amount set aside.
+pub fn allot_rewards (storage: &mut dyn Storage, title: &str, vote: &VoteStatus) -> Result<Uint128, ContractError> {
+    let mut pool = match may_load_reward_pool(storage)? {
+        Some(pool) => pool,
+        None => return Ok(Uint128::zero()),
+    };
+    let allotment = pool.per_proposal.min(pool.balance);
+    // the tally holds the power of every ballot
+    let total = turnout(vote)?;
+    if allotment.is_zero() || total.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    let per_power = Decimal::from_ratio(allotment, total);
+    let allotted = total * per_power;
+    store_proposal_reward(storage, title, &ProposalReward { per_power, unpaid: allotted, unclaimed_power: total })?;
+    pool.balance = pool.balance.checked_sub(allotted)?;
+    pool.pending = pool.pending.checked_add(allotted)?;
+    store_reward_pool(storage, &pool)?;
+    Ok(allotted)
+}
+
+// Unclaimed rewards of up to `limit` ballots of a voter after `start_after`.
+pub struct Claimable {
+    pub amount: Uint128,
+    // title and power of the unclaimed ballots the amount comes from
+    pub ballots: Vec<(String, Uint128)>,
+    // last title looked at when the page is full, more ballots may follow
+    pub last_title: Option<String>,
+}
+
+// Sums the rewards of the unclaimed ballots of a voter, one page of ballots at a time so
+// the cost does not grow with the voter history.
+pub fn claimable_rewards (storage: &dyn Storage, voter: &Addr, start_after: Option<String>, limit: usize) -> Result<Claimable, ContractError> {
+    let min = start_after.map(|title| Bound::exclusive(ballot_key(&title, voter)));
+    let ballots = range_ballots_by_voter(storage, voter, min, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_title = match ballots.last() {
+        Some((title, _)) if ballots.len() == limit => Some(title.clone()),
+        _ => None,
+    };
+    let mut amount = Uint128::zero();
+    let mut unclaimed = vec![];
+    for (title, ballot) in ballots {
+        let reward = match may_load_proposal_reward(storage, &title)? {
+            Some(reward) => reward,
+            None => continue,
+        };
+        if is_reward_claimed(storage, &title, voter)? {
+            continue;
+        }
+        amount = amount.checked_add(ballot.power * reward.per_power)?;
+        unclaimed.push((title, ballot.power));
+    }
+    Ok(Claimable { amount, ballots: unclaimed, last_title })
+}
+
+// Marks the ballots as claimed and takes their rewards out of the pool. The rounding dust
+// of a proposal goes back to the balance with the claim of its last unit of power.
+pub fn settle_claim (storage: &mut dyn Storage, pool: &mut RewardPool, voter: &Addr, claimable: &Claimable) -> Result<(), ContractError> {
+    for (title, power) in claimable.ballots.iter() {
+        store_reward_claimed(storage, title, voter)?;
+        let mut reward = may_load_proposal_reward(storage, title)?
+            .ok_or(ContractError::FailedToUpdateData {})?;
+        reward.unpaid = reward.unpaid.checked_sub(*power * reward.per_power)?;
+        reward.unclaimed_power = reward.unclaimed_power.checked_sub(*power)?;
+        if reward.unclaimed_power.is_zero() {
+            pool.pending = pool.pending.checked_sub(reward.unpaid)?;
+            pool.balance = pool.balance.checked_add(reward.unpaid)?;
+            reward.unpaid = Uint128::zero();
+        }
+        store_proposal_reward(storage, title, &reward)?;
+    }
+    pool.pending = pool.pending.checked_sub(claimable.amount)?;
+    Ok(())
+}
+
+pub fn reward_msg (asset: &RewardAsset, to: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    match asset {
+        RewardAsset::Native { denom } => Ok(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: vec![Coin { denom: denom.clone(), amount }],
+        }.into()),
+        RewardAsset::Cw20 { contract_addr } => Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: to.to_string(), amount })?,
+            funds: vec![],
+        }.into()),
+    }
+}
@@ -1,8 +1,12 @@
-use cosmwasm_std::{StdResult, Storage, StdError};
-use cw_storage_plus::{Item, Map};
+use std::convert::TryInto;
+use cosmwasm_std::{BlockInfo, Order, StdResult, Storage, StdError};
+use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, PrimaryKey, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128, Uint64};
+use governance_types::hooks::HookPolicy;
+use governance_types::types::{EmergencyPauseState, LateQuorum, RewardAsset, RefundPolicy, Outcome, ProposalCategory, ProposalRevision, ProposalStatus, StatsMismatch, Threshold};
+use cw0::{Duration, Expiration};
 
 // cs-storage-plus docs: https://crates.io/crates/cw-storage-plus
 
@@ -10,7 +14,13 @@ use cosmwasm_std::{Addr, Coin};
 pub struct Config {
     pub owner: Addr,
     pub admins: Vec<Addr>,
-    pub votes_titles: Vec<String>,
+    #[serde(default)]
+    pub guardian: Option<Addr>,
+    #[serde(default)]
+    pub unpause_delay: Option<Duration>,
+    // set while the whole contract is stopped by EmergencyPause
+    #[serde(default)]
+    pub emergency_pause: Option<EmergencyPauseState>,
 }
 const CONFIG: Item<Config> = Item::new("\u{0}\u{6}config");
 pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
@@ -26,13 +36,37 @@ E: From<StdError>,
     CONFIG.update(storage, action)
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct Stats {
-    pub in_progress: i32,
-    pub rejected_votes: i32,
-    pub accepted: i32,
-    pub not_resolved: i32,
-    pub paused: i32,
+    pub in_progress: Uint64,
+    pub rejected_votes: Uint64,
+    pub accepted: Uint64,
+    pub not_resolved: Uint64,
+    pub paused: Uint64,
+    pub vetoed: Uint64,
+    #[serde(default)]
+    pub cancelled: Uint64,
+
+    // For / Against / Abstain / NoWithVeto power over all proposals
+    #[serde(default)]
+    pub votes_for: Uint128,
+    #[serde(default)]
+    pub votes_against: Uint128,
+    #[serde(default)]
+    pub votes_abstain: Uint128,
+    #[serde(default)]
+    pub votes_no_with_veto: Uint128,
+    // ballots cast directly by voters
+    #[serde(default)]
+    pub ballots: Uint64,
+    // votes cast on finalized proposals
+    #[serde(default)]
+    pub finalized_turnout: Uint128,
+    // votes cast on / total power of finalized proposals with a known total power
+    #[serde(default)]
+    pub measured_turnout: Uint128,
+    #[serde(default)]
+    pub measured_power: Uint128,
 }
 const STATS: Item<Stats> = Item::new("\u{0}\u{6}stats");
 pub fn store_stats(storage: &mut dyn Storage, config: &Stats) -> StdResult<()> {
@@ -48,36 +82,570 @@ E: From<StdError>,
     STATS.update(storage, action)
 }
 
+// Stats as they were at the end of a block, written only for blocks where they changed.
+const STATS_HISTORY: Map<U64Key, Stats> = Map::new("STATS_HISTORY");
+pub fn store_stats_snapshot(storage: &mut dyn Storage, height: u64, stats: &Stats) -> StdResult<()> {
+    STATS_HISTORY.save(storage, U64Key::new(height), stats)
+}
+pub fn may_load_stats_at(storage: &dyn Storage, height: u64) -> StdResult<Option<(u64, Stats)>> {
+    range_stats_history(storage, None, Some(Bound::inclusive(U64Key::new(height))), Order::Descending)
+        .next()
+        .transpose()
+}
+pub fn range_stats_history<'a> (storage: &'a dyn Storage, min: Option<Bound>, max: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(u64, Stats)>> + 'a> {
+    Box::new(STATS_HISTORY
+        .range(storage, min, max, order)
+        .map(|item| -> StdResult<(u64, Stats)> {
+            let (key, stats) = item?;
+            let height: [u8; 8] = key.as_slice().try_into()
+                .map_err(|_| StdError::generic_err("Invalid stats history key"))?;
+            Ok((u64::from_be_bytes(height), stats))
+        }))
+}
+
+// Progress of a paginated RecomputeStats, `cursor` is the last processed title.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecomputeProgress {
+    pub cursor: Option<String>,
+    pub stats: Stats,
+}
+const RECOMPUTE: Item<RecomputeProgress> = Item::new("recompute");
+pub fn may_load_recompute(storage: &dyn Storage) -> StdResult<Option<RecomputeProgress>> {
+    RECOMPUTE.may_load(storage)
+}
+pub fn store_recompute(storage: &mut dyn Storage, progress: &RecomputeProgress) -> StdResult<()> {
+    RECOMPUTE.save(storage, progress)
+}
+pub fn remove_recompute(storage: &mut dyn Storage) {
+    RECOMPUTE.remove(storage)
+}
+
+// Outcome of the last completed RecomputeStats.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RecomputeReport {
+    pub height: u64,
+    pub mismatches: Vec<StatsMismatch>,
+}
+const LAST_RECOMPUTE: Item<RecomputeReport> = Item::new("last_recompute");
+pub fn may_load_last_recompute(storage: &dyn Storage) -> StdResult<Option<RecomputeReport>> {
+    LAST_RECOMPUTE.may_load(storage)
+}
+pub fn store_last_recompute(storage: &mut dyn Storage, report: &RecomputeReport) -> StdResult<()> {
+    LAST_RECOMPUTE.save(storage, report)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct CreatorStats {
+    pub created: Uint64,
+    pub accepted: Uint64,
+    pub rejected: Uint64,
+    pub not_resolved: Uint64,
+    pub vetoed: Uint64,
+    // votes cast on the creator's finalized proposals
+    pub finalized_turnout: Uint128,
+    #[serde(default)]
+    pub cancelled: Uint64,
+}
+const CREATOR_STATS: Map<&Addr, CreatorStats> = Map::new("CREATOR_STATS");
+pub fn may_load_creator_stats(storage: &dyn Storage, creator: &Addr) -> StdResult<Option<CreatorStats>> {
+    CREATOR_STATS.may_load(storage, creator)
+}
+pub fn update_creator_stats <A, E> (storage: &mut dyn Storage, creator: &Addr, action: A) -> Result<CreatorStats, E> where
+A: FnOnce(Option<CreatorStats>) -> Result<CreatorStats, E>,
+E: From<StdError>,
+{
+    CREATOR_STATS.update(storage, creator, action)
+}
+// Only admins create proposals, so there are few creators to go through.
+pub fn clear_creator_stats(storage: &mut dyn Storage) -> StdResult<()> {
+    let creators = CREATOR_STATS
+        .keys(storage, None, None, Order::Ascending)
+        .map(|key| -> StdResult<Addr> { Ok(Addr::unchecked(String::from_utf8(key)?)) })
+        .collect::<StdResult<Vec<_>>>()?;
+    for creator in creators {
+        CREATOR_STATS.remove(storage, &creator);
+    }
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct VoteStatus {
     pub paused: bool,
-    pub votes_for: i32,
-    pub votes_against: i32,
-    pub votes_abstain: i32,
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
 
     pub creator: Addr,
     pub required_coins_on: bool,
     pub required_coins: Coin,
-    pub min_votes_count: i32,
-    pub required_votes_percentage: i32,
-    pub already_participate: Vec<Addr>,
+    pub min_votes_count: Uint128,
+    pub threshold: Threshold,
+    pub veto_threshold: Decimal,
+    pub total_power: Option<u64>,
+    // number of ballots cast, the voters themselves are kept in BALLOTS
+    #[serde(default)]
+    pub ballots: Uint64,
     pub whitelist_on: bool,
     pub whitelist: Vec<Addr>,
+    pub outcome: Option<Outcome>,
+    pub category: String,
+    pub expires: Expiration,
+    pub deposit: Option<Coin>,
+    #[serde(default)]
+    pub cancel_refund: RefundPolicy,
+    // set once the proposal is cancelled
+    #[serde(default)]
+    pub cancel_reason: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub links: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // number of the last revision stored in REVISIONS
+    #[serde(default)]
+    pub revision: u64,
+    // `expires` before any pause extension, None for proposals created before extensions existed
+    #[serde(default)]
+    pub original_expires: Option<Expiration>,
+    #[serde(default)]
+    pub max_pause_extension: Option<Duration>,
+    // blocks or seconds already added to `expires` because of pauses
+    #[serde(default)]
+    pub pause_extension: u64,
+    #[serde(default)]
+    pub paused_at: Option<BlockStamp>,
+    #[serde(default)]
+    pub late_quorum: Option<LateQuorum>,
+    #[serde(default)]
+    pub quorum_reached_at: Option<BlockStamp>,
+    // number of the last emergency pause already added to `expires`
+    #[serde(default)]
+    pub emergency_pauses_seen: u64,
+}
+
+// Height and time of the block where something happened to a vote.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BlockStamp {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+// A completed EmergencyPause, numbered from 1. Proposals add it to their end when next read.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmergencyPauseRecord {
+    pub number: u64,
+    pub started: BlockStamp,
+    pub blocks: u64,
+    pub seconds: u64,
+}
+const EMERGENCY_PAUSES: Map<U64Key, EmergencyPauseRecord> = Map::new("EMERGENCY_PAUSES");
+const EMERGENCY_PAUSE_COUNT: Item<u64> = Item::new("emergency_pause_count");
+pub fn load_emergency_pause_count(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(EMERGENCY_PAUSE_COUNT.may_load(storage)?.unwrap_or_default())
+}
+pub fn store_emergency_pause(storage: &mut dyn Storage, started: BlockStamp, blocks: u64, seconds: u64) -> StdResult<u64> {
+    let number = load_emergency_pause_count(storage)? + 1;
+    EMERGENCY_PAUSES.save(storage, U64Key::new(number), &EmergencyPauseRecord { number, started, blocks, seconds })?;
+    EMERGENCY_PAUSE_COUNT.save(storage, &number)?;
+    Ok(number)
+}
+// Pauses numbered after `seen`, oldest first.
+pub fn range_emergency_pauses<'a> (storage: &'a dyn Storage, seen: u64) -> Box<dyn Iterator<Item = StdResult<EmergencyPauseRecord>> + 'a> {
+    Box::new(EMERGENCY_PAUSES
+        .range(storage, Some(Bound::exclusive(U64Key::new(seen))), None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record)))
 }
 
-const VOTES: Map<&str, VoteStatus> = Map::new("VOTES");
+impl VoteStatus {
+    pub fn status(&self) -> ProposalStatus {
+        if self.cancel_reason.is_some() {
+            return ProposalStatus::Cancelled;
+        }
+        match self.outcome {
+            Some(Outcome::Accepted) => ProposalStatus::Accepted,
+            Some(Outcome::Rejected) => ProposalStatus::Rejected,
+            Some(Outcome::NotResolved) => ProposalStatus::NotResolved,
+            Some(Outcome::Vetoed) => ProposalStatus::Vetoed,
+            None if self.paused => ProposalStatus::Paused,
+            None => ProposalStatus::InProgress,
+        }
+    }
+}
 
-pub fn may_load_vote (storage: &dyn Storage, key: &String) -> StdResult<Option<VoteStatus>> {
-    VOTES.may_load(storage, key)
+pub struct VoteIndexes<'a> {
+    pub status: MultiIndex<'a, (Vec<u8>, Vec<u8>), VoteStatus>,
+    pub creator: MultiIndex<'a, (Vec<u8>, Vec<u8>), VoteStatus>,
+    pub status_creator: MultiIndex<'a, (Vec<u8>, Vec<u8>), VoteStatus>,
+    pub end: MultiIndex<'a, (Vec<u8>, Vec<u8>), VoteStatus>,
+}
+impl<'a> IndexList<VoteStatus> for VoteIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<VoteStatus>> + '_> {
+        let v: Vec<&dyn Index<VoteStatus>> = vec![&self.status, &self.creator, &self.status_creator, &self.end];
+        Box::new(v.into_iter())
+    }
 }
-pub fn load_vote (storage: &dyn Storage, key: &String) -> StdResult<VoteStatus> {
-    VOTES.load(storage, key)
+
+// Proposals are keyed by title and indexed by status, creator, both together and voting end.
+fn votes<'a>() -> IndexedMap<'a, &'a str, VoteStatus, VoteIndexes<'a>> {
+    let indexes = VoteIndexes {
+        status: MultiIndex::new(
+            |vote, pk| (status_key(&vote.status()), pk),
+            "VOTES",
+            "VOTES__status",
+        ),
+        creator: MultiIndex::new(
+            |vote, pk| (vote.creator.as_bytes().to_vec(), pk),
+            "VOTES",
+            "VOTES__creator",
+        ),
+        status_creator: MultiIndex::new(
+            |vote, pk| (status_creator_key(&vote.status(), &vote.creator), pk),
+            "VOTES",
+            "VOTES__status_creator",
+        ),
+        end: MultiIndex::new(
+            |vote, pk| (end_key(vote), pk),
+            "VOTES",
+            "VOTES__end",
+        ),
+    };
+    IndexedMap::new("VOTES", indexes)
 }
-pub fn store_vote (storage: &mut dyn Storage, key: &String, data: VoteStatus) -> StdResult<()> {
-    VOTES.save(storage, key, &data)
+
+pub fn status_key(status: &ProposalStatus) -> Vec<u8> {
+    let key = match status {
+        ProposalStatus::InProgress => "in_progress",
+        ProposalStatus::Paused => "paused",
+        ProposalStatus::Accepted => "accepted",
+        ProposalStatus::Rejected => "rejected",
+        ProposalStatus::NotResolved => "not_resolved",
+        ProposalStatus::Vetoed => "vetoed",
+        ProposalStatus::Cancelled => "cancelled",
+    };
+    key.as_bytes().to_vec()
+}
+
+fn status_creator_key(status: &ProposalStatus, creator: &Addr) -> Vec<u8> {
+    (status_key(status).as_slice(), creator.as_bytes()).joined_key()
+}
+
+// Kind of the voting end followed by the height or time in big endian, so that proposals of
+// each kind sort by end. Finalized and cancelled proposals are set apart, only open ones are
+// ever looked up.
+const END_AT_HEIGHT: u8 = 1;
+const END_AT_TIME: u8 = 2;
+const END_NEVER: u8 = 3;
+const END_FINALIZED: u8 = 4;
+fn end_key_of(kind: u8, value: u64) -> Vec<u8> {
+    let mut key = vec![kind];
+    key.extend_from_slice(&value.to_be_bytes());
+    key
+}
+fn end_key(vote: &VoteStatus) -> Vec<u8> {
+    if vote.outcome.is_some() || vote.cancel_reason.is_some() {
+        return end_key_of(END_FINALIZED, 0);
+    }
+    match vote.expires {
+        Expiration::AtHeight(height) => end_key_of(END_AT_HEIGHT, height),
+        Expiration::AtTime(time) => end_key_of(END_AT_TIME, time.nanos()),
+        Expiration::Never {} => end_key_of(END_NEVER, 0),
+    }
+}
+
+pub fn may_load_vote (storage: &dyn Storage, key: &str) -> StdResult<Option<VoteStatus>> {
+    votes().may_load(storage, key)
 }
-pub fn update_vote <A, E> (storage: &mut dyn Storage, key: &String, action: A ) -> Result<VoteStatus, E> where
+pub fn load_vote (storage: &dyn Storage, key: &str) -> StdResult<VoteStatus> {
+    votes().load(storage, key)
+}
+pub fn store_vote (storage: &mut dyn Storage, key: &str, data: VoteStatus) -> StdResult<()> {
+    votes().save(storage, key, &data)
+}
+pub fn update_vote <A, E> (storage: &mut dyn Storage, key: &str, action: A ) -> Result<VoteStatus, E> where
 A: FnOnce(Option<VoteStatus>) -> Result<VoteStatus, E>,
 E: From<StdError>, {
-    VOTES.update(storage, key, action)
-}
\ No newline at end of file
+    votes().update(storage, key, action)
+}
+pub fn range_votes<'a> (storage: &'a dyn Storage, min: Option<Bound>, max: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    Box::new(votes()
+        .range(storage, min, max, order)
+        .map(title_and_vote))
+}
+pub fn load_vote_titles (storage: &dyn Storage) -> StdResult<Vec<String>> {
+    votes()
+        .prefix(())
+        .keys(storage, None, None, Order::Ascending)
+        .map(|key| -> StdResult<String> { Ok(String::from_utf8(key)?) })
+        .collect()
+}
+// Bounds are on the title, like in range_votes.
+pub fn range_votes_by_status<'a> (storage: &'a dyn Storage, status: &ProposalStatus, min: Option<Bound>, max: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    Box::new(votes()
+        .idx
+        .status
+        .prefix(status_key(status))
+        .range(storage, min, max, order)
+        .map(title_and_vote))
+}
+pub fn range_votes_by_creator<'a> (storage: &'a dyn Storage, creator: &Addr, min: Option<Bound>, max: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    Box::new(votes()
+        .idx
+        .creator
+        .prefix(creator.as_bytes().to_vec())
+        .range(storage, min, max, order)
+        .map(title_and_vote))
+}
+pub fn range_votes_by_status_and_creator<'a> (storage: &'a dyn Storage, status: &ProposalStatus, creator: &Addr, min: Option<Bound>, max: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    Box::new(votes()
+        .idx
+        .status_creator
+        .prefix(status_creator_key(status, creator))
+        .range(storage, min, max, order)
+        .map(title_and_vote))
+}
+// Open proposals whose voting period is over at `block`, the earliest ended first.
+pub fn range_ended_votes<'a> (storage: &'a dyn Storage, block: &BlockInfo) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    let by_height = range_ended(storage, END_AT_HEIGHT, block.height);
+    let by_time = range_ended(storage, END_AT_TIME, block.time.nanos());
+    Box::new(by_height.chain(by_time))
+}
+fn range_ended<'a> (storage: &'a dyn Storage, kind: u8, now: u64) -> Box<dyn Iterator<Item = StdResult<(String, VoteStatus)>> + 'a> {
+    let index = votes().idx.end;
+    let min = index.index_key((end_key_of(kind, 0), vec![]));
+    // expirations are reached once now >= end
+    let max = match now.checked_add(1) {
+        Some(next) => Bound::exclusive(index.index_key((end_key_of(kind, next), vec![]))),
+        None => Bound::exclusive(index.index_key((end_key_of(kind + 1, 0), vec![]))),
+    };
+    Box::new(index
+        .sub_prefix(())
+        .range(storage, Some(Bound::inclusive(min)), Some(max), Order::Ascending)
+        .map(title_and_vote))
+}
+fn title_and_vote(item: StdResult<(Vec<u8>, VoteStatus)>) -> StdResult<(String, VoteStatus)> {
+    let (key, vote) = item?;
+    Ok((String::from_utf8(key)?, vote))
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    pub vote: String,
+    pub power: Uint128,
+}
+
+pub struct BallotIndexes<'a> {
+    pub voter: MultiIndex<'a, (Vec<u8>, Vec<u8>), Ballot>,
+}
+impl<'a> IndexList<Ballot> for BallotIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Ballot>> + '_> {
+        let v: Vec<&dyn Index<Ballot>> = vec![&self.voter];
+        Box::new(v.into_iter())
+    }
+}
+
+// Ballots are keyed by (title, voter) and indexed by voter for the voter history.
+fn ballots<'a>() -> IndexedMap<'a, (&'a str, &'a Addr), Ballot, BallotIndexes<'a>> {
+    let indexes = BallotIndexes {
+        voter: MultiIndex::new(
+            |_, pk| (split_ballot_key(&pk).1.as_bytes().to_vec(), pk),
+            "BALLOTS",
+            "BALLOTS__voter",
+        ),
+    };
+    IndexedMap::new("BALLOTS", indexes)
+}
+
+// Splits a raw (title, voter) key, the title is stored with a 2 bytes length prefix.
+pub fn split_ballot_key(pk: &[u8]) -> (String, String) {
+    let title_len = u16::from_be_bytes([pk[0], pk[1]]) as usize;
+    let title = String::from_utf8_lossy(&pk[2..2 + title_len]).to_string();
+    let voter = String::from_utf8_lossy(&pk[2 + title_len..]).to_string();
+    (title, voter)
+}
+
+pub fn ballot_key(title: &str, voter: &Addr) -> Vec<u8> {
+    (title, voter).joined_key()
+}
+
+pub fn may_load_ballot (storage: &dyn Storage, title: &str, voter: &Addr) -> StdResult<Option<Ballot>> {
+    ballots().may_load(storage, (title, voter))
+}
+pub fn store_ballot (storage: &mut dyn Storage, title: &str, voter: &Addr, data: &Ballot) -> StdResult<()> {
+    ballots().save(storage, (title, voter), data)
+}
+pub fn range_ballots_by_title<'a> (storage: &'a dyn Storage, title: &str, min: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(Addr, Ballot)>> + 'a> {
+    Box::new(ballots()
+        .prefix(title)
+        .range(storage, min, None, order)
+        .map(|item| -> StdResult<(Addr, Ballot)> {
+            let (key, ballot) = item?;
+            Ok((Addr::unchecked(String::from_utf8(key)?), ballot))
+        }))
+}
+pub fn range_ballots_by_voter<'a> (storage: &'a dyn Storage, voter: &Addr, min: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<(String, Ballot)>> + 'a> {
+    Box::new(ballots()
+        .idx
+        .voter
+        .prefix(voter.as_bytes().to_vec())
+        .range(storage, min, None, order)
+        .map(|item| -> StdResult<(String, Ballot)> {
+            let (key, ballot) = item?;
+            Ok((split_ballot_key(&key).0, ballot))
+        }))
+}
+
+// delegator -> delegate
+const DELEGATIONS: Map<&Addr, Addr> = Map::new("DELEGATIONS");
+// (delegate, delegator), reverse index of DELEGATIONS
+const DELEGATORS: Map<(&Addr, &Addr), bool> = Map::new("DELEGATORS");
+
+pub fn may_load_delegation (storage: &dyn Storage, delegator: &Addr) -> StdResult<Option<Addr>> {
+    DELEGATIONS.may_load(storage, delegator)
+}
+pub fn store_delegation (storage: &mut dyn Storage, delegator: &Addr, delegate: &Addr) -> StdResult<()> {
+    DELEGATIONS.save(storage, delegator, delegate)?;
+    DELEGATORS.save(storage, (delegate, delegator), &true)
+}
+pub fn remove_delegation (storage: &mut dyn Storage, delegator: &Addr, delegate: &Addr) {
+    DELEGATIONS.remove(storage, delegator);
+    DELEGATORS.remove(storage, (delegate, delegator));
+}
+pub fn load_delegators (storage: &dyn Storage, delegate: &Addr) -> StdResult<Vec<Addr>> {
+    DELEGATORS
+        .prefix(delegate)
+        .keys(storage, None, None, Order::Ascending)
+        .map(|key| -> StdResult<Addr> { Ok(Addr::unchecked(String::from_utf8(key)?)) })
+        .collect()
+}
+
+// (title, revision) -> edit made with UpdateProposal
+const REVISIONS: Map<(&str, U64Key), ProposalRevision> = Map::new("REVISIONS");
+
+pub fn store_revision (storage: &mut dyn Storage, title: &str, data: &ProposalRevision) -> StdResult<()> {
+    REVISIONS.save(storage, (title, U64Key::new(data.revision)), data)
+}
+pub fn range_revisions<'a> (storage: &'a dyn Storage, title: &str, min: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<ProposalRevision>> + 'a> {
+    Box::new(REVISIONS
+        .prefix(title)
+        .range(storage, min, None, order)
+        .map(|item| item.map(|(_, revision)| revision)))
+}
+
+// (tag, title) -> true for every tag of a proposal
+const PROPOSAL_TAGS: Map<(&str, &str), bool> = Map::new("PROPOSAL_TAGS");
+
+pub fn store_tags (storage: &mut dyn Storage, title: &str, tags: &[String]) -> StdResult<()> {
+    for tag in tags {
+        PROPOSAL_TAGS.save(storage, (tag, title), &true)?;
+    }
+    Ok(())
+}
+pub fn remove_tags (storage: &mut dyn Storage, title: &str, tags: &[String]) {
+    for tag in tags {
+        PROPOSAL_TAGS.remove(storage, (tag, title));
+    }
+}
+pub fn range_titles_by_tag<'a> (storage: &'a dyn Storage, tag: &str, min: Option<Bound>, order: Order) -> Box<dyn Iterator<Item = StdResult<String>> + 'a> {
+    Box::new(PROPOSAL_TAGS
+        .prefix(tag)
+        .keys(storage, min, None, order)
+        .map(|key| -> StdResult<String> { Ok(String::from_utf8(key)?) }))
+}
+
+// (title, address) -> voter whose ballot currently carries the address's power
+const CAST_VIA: Map<(&str, &Addr), Addr> = Map::new("CAST_VIA");
+
+pub fn may_load_cast_via (storage: &dyn Storage, title: &str, address: &Addr) -> StdResult<Option<Addr>> {
+    CAST_VIA.may_load(storage, (title, address))
+}
+pub fn store_cast_via (storage: &mut dyn Storage, title: &str, address: &Addr, voter: &Addr) -> StdResult<()> {
+    CAST_VIA.save(storage, (title, address), voter)
+}
+pub fn remove_cast_via (storage: &mut dyn Storage, title: &str, address: &Addr) {
+    CAST_VIA.remove(storage, (title, address))
+}
+
+const CATEGORIES: Map<&str, ProposalCategory> = Map::new("CATEGORIES");
+
+pub fn may_load_category (storage: &dyn Storage, name: &str) -> StdResult<Option<ProposalCategory>> {
+    CATEGORIES.may_load(storage, name)
+}
+pub fn store_category (storage: &mut dyn Storage, name: &str, data: &ProposalCategory) -> StdResult<()> {
+    CATEGORIES.save(storage, name, data)
+}
+pub fn remove_category (storage: &mut dyn Storage, name: &str) {
+    CATEGORIES.remove(storage, name)
+}
+pub fn load_category_names (storage: &dyn Storage) -> StdResult<Vec<String>> {
+    CATEGORIES
+        .keys(storage, None, None, Order::Ascending)
+        .map(|key| -> StdResult<String> { Ok(String::from_utf8(key)?) })
+        .collect()
+}
+
+// Contracts notified of governance events, with what to do when they fail.
+const HOOKS: Map<&Addr, HookPolicy> = Map::new("HOOKS");
+
+pub fn may_load_hook (storage: &dyn Storage, addr: &Addr) -> StdResult<Option<HookPolicy>> {
+    HOOKS.may_load(storage, addr)
+}
+pub fn store_hook (storage: &mut dyn Storage, addr: &Addr, policy: &HookPolicy) -> StdResult<()> {
+    HOOKS.save(storage, addr, policy)
+}
+pub fn remove_hook (storage: &mut dyn Storage, addr: &Addr) {
+    HOOKS.remove(storage, addr)
+}
+pub fn load_hooks (storage: &dyn Storage) -> StdResult<Vec<(Addr, HookPolicy)>> {
+    HOOKS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| -> StdResult<(Addr, HookPolicy)> {
+            let (key, policy) = item?;
+            Ok((Addr::unchecked(String::from_utf8(key)?), policy))
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardPool {
+    pub asset: RewardAsset,
+    pub per_proposal: Uint128,
+    // funds not yet allotted to any proposal
+    pub balance: Uint128,
+    // funds allotted to voters but not claimed yet
+    pub pending: Uint128,
+}
+const REWARD_POOL: Item<RewardPool> = Item::new("reward_pool");
+pub fn may_load_reward_pool(storage: &dyn Storage) -> StdResult<Option<RewardPool>> {
+    REWARD_POOL.may_load(storage)
+}
+pub fn store_reward_pool(storage: &mut dyn Storage, pool: &RewardPool) -> StdResult<()> {
+    REWARD_POOL.save(storage, pool)
+}
+
+// Rewards set aside for the voters of a finalized proposal.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalReward {
+    // reward of each unit of power cast on the proposal
+    pub per_power: Decimal,
+    // part of the allotment not paid out yet
+    pub unpaid: Uint128,
+    // power of the ballots not claimed yet
+    pub unclaimed_power: Uint128,
+}
+const PROPOSAL_REWARDS: Map<&str, ProposalReward> = Map::new("PROPOSAL_REWARDS");
+pub fn may_load_proposal_reward (storage: &dyn Storage, title: &str) -> StdResult<Option<ProposalReward>> {
+    PROPOSAL_REWARDS.may_load(storage, title)
+}
+pub fn store_proposal_reward (storage: &mut dyn Storage, title: &str, reward: &ProposalReward) -> StdResult<()> {
+    PROPOSAL_REWARDS.save(storage, title, reward)
+}
+
+// (title, voter) -> ballot reward already paid out
+const CLAIMED_REWARDS: Map<(&str, &Addr), bool> = Map::new("CLAIMED_REWARDS");
+pub fn is_reward_claimed (storage: &dyn Storage, title: &str, voter: &Addr) -> StdResult<bool> {
+    Ok(CLAIMED_REWARDS.may_load(storage, (title, voter))?.unwrap_or(false))
+}
+pub fn store_reward_claimed (storage: &mut dyn Storage, title: &str, voter: &Addr) -> StdResult<()> {
+    CLAIMED_REWARDS.save(storage, (title, voter), &true)
+}
@@ -1,45 +1,231 @@
-use cosmwasm_std::{Storage};
+use cosmwasm_std::{Addr, Storage, Uint128, Uint64};
 use governance_types::errors::ContractError;
-use crate::state::{ Stats, update_stats };
+use governance_types::types::{Outcome, StatsMismatch};
+use crate::state::{ Stats, CreatorStats, VoteStatus, update_stats, update_creator_stats, read_stats, may_load_stats_at,
+    store_stats_snapshot };
 
 pub fn add_in_progress (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.in_progress+=1;
+        stats.in_progress = stats.in_progress.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
 }
 pub fn set_to_resolved (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.in_progress-=1;
-        stats.not_resolved+=1;
+        stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        stats.not_resolved = stats.not_resolved.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
 }
 pub fn set_to_rejected (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.in_progress-=1;
-        stats.rejected_votes+=1;
+        stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        stats.rejected_votes = stats.rejected_votes.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
 }
 pub fn set_to_accepted (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.in_progress-=1;
-        stats.accepted+=1;
+        stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        stats.accepted = stats.accepted.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
 }
 pub fn set_to_paused (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.in_progress-=1;
-        stats.paused+=1;
+        stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        stats.paused = stats.paused.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
 }
 pub fn set_to_un_paused (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
     update_stats(storage, |mut stats| -> Result<_, ContractError> {
-        stats.paused-=1;
-        stats.in_progress+=1;
+        stats.paused = stats.paused.checked_sub(Uint64::new(1))?;
+        stats.in_progress = stats.in_progress.checked_add(Uint64::new(1))?;
         Ok(stats)
     })
-}
\ No newline at end of file
+}
+pub fn set_to_vetoed (storage: &mut dyn Storage) -> Result<Stats, ContractError> {
+    update_stats(storage, |mut stats| -> Result<_, ContractError> {
+        stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        stats.vetoed = stats.vetoed.checked_add(Uint64::new(1))?;
+        Ok(stats)
+    })
+}
+pub fn set_to_cancelled (storage: &mut dyn Storage, vote: &VoteStatus) -> Result<Stats, ContractError> {
+    update_creator_stats(storage, &vote.creator, |creator_stats| -> Result<_, ContractError> {
+        let mut creator_stats = creator_stats.unwrap_or_default();
+        creator_stats.cancelled = creator_stats.cancelled.checked_add(Uint64::new(1))?;
+        Ok(creator_stats)
+    })?;
+    update_stats(storage, |mut stats| -> Result<_, ContractError> {
+        if vote.paused {
+            stats.paused = stats.paused.checked_sub(Uint64::new(1))?;
+        } else {
+            stats.in_progress = stats.in_progress.checked_sub(Uint64::new(1))?;
+        }
+        stats.cancelled = stats.cancelled.checked_add(Uint64::new(1))?;
+        Ok(stats)
+    })
+}
+
+fn total_for<'a>(stats: &'a mut Stats, option: &str) -> Result<&'a mut Uint128, ContractError> {
+    match option {
+        "For" => Ok(&mut stats.votes_for),
+        "Against" => Ok(&mut stats.votes_against),
+        "Abstain" => Ok(&mut stats.votes_abstain),
+        "NoWithVeto" => Ok(&mut stats.votes_no_with_veto),
+        _ => Err(ContractError::VoteNotValid {}),
+    }
+}
+// `moved` holds the options of ballots which lost one unit of power to the new ballot.
+pub fn add_ballot (storage: &mut dyn Storage, option: &str, power: Uint128, moved: &[String]) -> Result<Stats, ContractError> {
+    update_stats(storage, |mut stats| -> Result<_, ContractError> {
+        for previous in moved {
+            let total = total_for(&mut stats, previous)?;
+            *total = total.checked_sub(Uint128::new(1))?;
+        }
+        let total = total_for(&mut stats, option)?;
+        *total = total.checked_add(power)?;
+        stats.ballots = stats.ballots.checked_add(Uint64::new(1))?;
+        Ok(stats)
+    })
+}
+pub fn add_finalized_turnout (storage: &mut dyn Storage, vote: &VoteStatus) -> Result<Stats, ContractError> {
+    let turnout = turnout(vote)?;
+    update_stats(storage, |mut stats| -> Result<_, ContractError> {
+        stats.finalized_turnout = stats.finalized_turnout.checked_add(turnout)?;
+        if let Some(total_power) = vote.total_power {
+            stats.measured_turnout = stats.measured_turnout.checked_add(turnout)?;
+            stats.measured_power = stats.measured_power.checked_add(Uint128::from(total_power))?;
+        }
+        Ok(stats)
+    })
+}
+pub fn add_created (storage: &mut dyn Storage, creator: &Addr) -> Result<CreatorStats, ContractError> {
+    update_creator_stats(storage, creator, |creator_stats| -> Result<_, ContractError> {
+        let mut creator_stats = creator_stats.unwrap_or_default();
+        creator_stats.created = creator_stats.created.checked_add(Uint64::new(1))?;
+        Ok(creator_stats)
+    })
+}
+pub fn add_creator_outcome (storage: &mut dyn Storage, vote: &VoteStatus, outcome: &Outcome) -> Result<CreatorStats, ContractError> {
+    let turnout = turnout(vote)?;
+    update_creator_stats(storage, &vote.creator, |creator_stats| -> Result<_, ContractError> {
+        let mut creator_stats = creator_stats.unwrap_or_default();
+        let counter = match outcome {
+            Outcome::Accepted => &mut creator_stats.accepted,
+            Outcome::Rejected => &mut creator_stats.rejected,
+            Outcome::NotResolved => &mut creator_stats.not_resolved,
+            Outcome::Vetoed => &mut creator_stats.vetoed,
+        };
+        *counter = counter.checked_add(Uint64::new(1))?;
+        creator_stats.finalized_turnout = creator_stats.finalized_turnout.checked_add(turnout)?;
+        Ok(creator_stats)
+    })
+}
+pub fn turnout (vote: &VoteStatus) -> Result<Uint128, ContractError> {
+    Ok(vote.votes_for
+        .checked_add(vote.votes_against)?
+        .checked_add(vote.votes_abstain)?
+        .checked_add(vote.votes_no_with_veto)?)
+}
+
+// Adds one stored proposal to stats rebuilt from scratch.
+pub fn accumulate (stats: &mut Stats, vote: &VoteStatus) -> Result<(), ContractError> {
+    let one = Uint64::new(1);
+    match (&vote.outcome, vote.paused) {
+        _ if vote.cancel_reason.is_some() => stats.cancelled = stats.cancelled.checked_add(one)?,
+        (None, true) => stats.paused = stats.paused.checked_add(one)?,
+        (None, false) => stats.in_progress = stats.in_progress.checked_add(one)?,
+        (Some(Outcome::Accepted), _) => stats.accepted = stats.accepted.checked_add(one)?,
+        (Some(Outcome::Rejected), _) => stats.rejected_votes = stats.rejected_votes.checked_add(one)?,
+        (Some(Outcome::NotResolved), _) => stats.not_resolved = stats.not_resolved.checked_add(one)?,
+        (Some(Outcome::Vetoed), _) => stats.vetoed = stats.vetoed.checked_add(one)?,
+    }
+    stats.votes_for = stats.votes_for.checked_add(vote.votes_for)?;
+    stats.votes_against = stats.votes_against.checked_add(vote.votes_against)?;
+    stats.votes_abstain = stats.votes_abstain.checked_add(vote.votes_abstain)?;
+    stats.votes_no_with_veto = stats.votes_no_with_veto.checked_add(vote.votes_no_with_veto)?;
+    stats.ballots = stats.ballots.checked_add(vote.ballots)?;
+    if vote.outcome.is_some() {
+        let turnout = turnout(vote)?;
+        stats.finalized_turnout = stats.finalized_turnout.checked_add(turnout)?;
+        if let Some(total_power) = vote.total_power {
+            stats.measured_turnout = stats.measured_turnout.checked_add(turnout)?;
+            stats.measured_power = stats.measured_power.checked_add(Uint128::from(total_power))?;
+        }
+    }
+    Ok(())
+}
+
+// Adds one stored proposal to the stats of its creator, rebuilt from scratch.
+pub fn accumulate_creator (storage: &mut dyn Storage, vote: &VoteStatus) -> Result<CreatorStats, ContractError> {
+    let one = Uint64::new(1);
+    let turnout = turnout(vote)?;
+    update_creator_stats(storage, &vote.creator, |creator_stats| -> Result<_, ContractError> {
+        let mut creator_stats = creator_stats.unwrap_or_default();
+        creator_stats.created = creator_stats.created.checked_add(one)?;
+        let counter = match &vote.outcome {
+            _ if vote.cancel_reason.is_some() => &mut creator_stats.cancelled,
+            None => return Ok(creator_stats),
+            Some(Outcome::Accepted) => &mut creator_stats.accepted,
+            Some(Outcome::Rejected) => &mut creator_stats.rejected,
+            Some(Outcome::NotResolved) => &mut creator_stats.not_resolved,
+            Some(Outcome::Vetoed) => &mut creator_stats.vetoed,
+        };
+        *counter = counter.checked_add(one)?;
+        if vote.outcome.is_some() {
+            creator_stats.finalized_turnout = creator_stats.finalized_turnout.checked_add(turnout)?;
+        }
+        Ok(creator_stats)
+    })
+}
+
+// Every Stats counter by name, used to compare stored and recomputed stats.
+pub fn stats_fields (stats: &Stats) -> Vec<(&'static str, Uint128)> {
+    vec![
+        ("in_progress", stats.in_progress.u64().into()),
+        ("rejected_votes", stats.rejected_votes.u64().into()),
+        ("accepted", stats.accepted.u64().into()),
+        ("not_resolved", stats.not_resolved.u64().into()),
+        ("cancelled", stats.cancelled.u64().into()),
+        ("paused", stats.paused.u64().into()),
+        ("vetoed", stats.vetoed.u64().into()),
+        ("votes_for", stats.votes_for),
+        ("votes_against", stats.votes_against),
+        ("votes_abstain", stats.votes_abstain),
+        ("votes_no_with_veto", stats.votes_no_with_veto),
+        ("ballots", stats.ballots.u64().into()),
+        ("finalized_turnout", stats.finalized_turnout),
+        ("measured_turnout", stats.measured_turnout),
+        ("measured_power", stats.measured_power),
+    ]
+}
+
+// Counters whose stored value differs from the recomputed one.
+pub fn stats_mismatches (stored: &Stats, recomputed: &Stats) -> Vec<StatsMismatch> {
+    stats_fields(stored)
+        .into_iter()
+        .zip(stats_fields(recomputed))
+        .filter(|((_, stored), (_, recomputed))| stored != recomputed)
+        .map(|((field, stored), (_, recomputed))| StatsMismatch {
+            field: field.to_string(),
+            stored,
+            recomputed,
+        })
+        .collect()
+}
+
+// Records current stats for `height` unless they equal the latest recorded ones.
+pub fn record_stats_snapshot (storage: &mut dyn Storage, height: u64) -> Result<(), ContractError> {
+    let stats = read_stats(storage)?;
+    let changed = match may_load_stats_at(storage, height)? {
+        Some((_, last)) => last != stats,
+        None => true,
+    };
+    if changed {
+        store_stats_snapshot(storage, height, &stats)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use cosmwasm_std::{BlockInfo, Decimal, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw0::{Duration, Expiration};
+use governance_types::errors::ContractError;
+use governance_types::types::{Outcome, Threshold};
+use crate::state::{VoteStatus, range_emergency_pauses};
+
+// Evaluates the result of a vote from its current counters.
+// Abstain votes count toward `min_votes_count`, the quorum and the veto share, but not toward
+// the threshold.
+pub fn compute_outcome(vote: &VoteStatus) -> Result<Outcome, ContractError> {
+    let decisive = vote.votes_for
+        .checked_add(vote.votes_against)?
+        .checked_add(vote.votes_no_with_veto)?;
+    let total = decisive.checked_add(vote.votes_abstain)?;
+    if total.is_zero() || total < vote.min_votes_count {
+        return Ok(Outcome::NotResolved);
+    }
+    if Decimal::from_ratio(vote.votes_no_with_veto, total) > vote.veto_threshold {
+        return Ok(Outcome::Vetoed);
+    }
+    if decisive.is_zero() {
+        return Ok(Outcome::NotResolved);
+    }
+    let passed = match vote.threshold {
+        Threshold::AbsoluteCount { weight } => vote.votes_for >= Uint128::from(weight),
+        Threshold::AbsolutePercentage { percentage } => {
+            let total_power = vote.total_power.map_or(total, Uint128::from);
+            // only the owner can vote on an empty whitelist, it carries no voting power
+            if total_power.is_zero() {
+                return Ok(Outcome::NotResolved);
+            }
+            threshold_share(vote)? >= percentage
+        }
+        Threshold::ThresholdQuorum { threshold, quorum } => {
+            let total_power = vote.total_power.map_or(total, Uint128::from);
+            if total_power.is_zero() || Decimal::from_ratio(total, total_power) < quorum {
+                return Ok(Outcome::NotResolved);
+            }
+            threshold_share(vote)? >= threshold
+        }
+    };
+    if passed {
+        Ok(Outcome::Accepted)
+    } else {
+        Ok(Outcome::Rejected)
+    }
+}
+
+// Share of For votes the threshold of the vote is compared with, zero when there is nothing
+// to measure it against.
+pub fn threshold_share(vote: &VoteStatus) -> Result<Decimal, ContractError> {
+    let decisive = vote.votes_for
+        .checked_add(vote.votes_against)?
+        .checked_add(vote.votes_no_with_veto)?;
+    let denominator = match vote.threshold {
+        Threshold::AbsoluteCount { weight } => Uint128::from(weight),
+        Threshold::AbsolutePercentage { .. } => {
+            let total = decisive.checked_add(vote.votes_abstain)?;
+            vote.total_power.map_or(total, Uint128::from)
+        }
+        Threshold::ThresholdQuorum { .. } => decisive,
+    };
+    if denominator.is_zero() {
+        return Ok(Decimal::zero());
+    }
+    Ok(Decimal::from_ratio(vote.votes_for, denominator))
+}
+
+// Whether the votes cast so far reach `min_votes_count`, abstain votes included.
+pub fn quorum_reached(vote: &VoteStatus) -> Result<bool, ContractError> {
+    let total = vote.votes_for
+        .checked_add(vote.votes_against)?
+        .checked_add(vote.votes_no_with_veto)?
+        .checked_add(vote.votes_abstain)?;
+    Ok(!total.is_zero() && total >= vote.min_votes_count)
+}
+
+// Time or blocks left until the vote expires, None for votes without a voting period.
+pub fn remaining_period(expires: &Expiration, block: &BlockInfo) -> Option<Duration> {
+    match *expires {
+        Expiration::AtHeight(height) => Some(Duration::Height(height.saturating_sub(block.height))),
+        Expiration::AtTime(time) => Some(Duration::Time(time.seconds().saturating_sub(block.time.seconds()))),
+        Expiration::Never {} => None,
+    }
+}
+
+// `expires` moved `by` blocks or seconds later.
+pub fn extend_expiration(expires: &Expiration, by: u64) -> Result<Expiration, ContractError> {
+    match *expires {
+        Expiration::AtHeight(height) => Ok(Expiration::AtHeight(Uint64::new(height).checked_add(Uint64::new(by))?.u64())),
+        Expiration::AtTime(time) => {
+            let nanos = Uint64::new(by).checked_mul(Uint64::new(1_000_000_000))?;
+            Ok(Expiration::AtTime(Timestamp::from_nanos(Uint64::new(time.nanos()).checked_add(nanos)?.u64())))
+        }
+        Expiration::Never {} => Ok(Expiration::Never {}),
+    }
+}
+
+// Blocks or seconds a pause can still add to the vote, by its own Pause or by EmergencyPause.
+pub fn pause_cap_left(vote: &VoteStatus) -> u64 {
+    match vote.max_pause_extension {
+        Some(Duration::Height(cap)) | Some(Duration::Time(cap)) => cap.saturating_sub(vote.pause_extension),
+        None => u64::MAX,
+    }
+}
+
+// Adds the emergency pauses the vote has not seen yet to its end, returns whether the vote changed.
+// Pauses which started once the vote was over are skipped, as are pauses the vote spent paused
+// on its own since its Unpause covers them.
+pub fn apply_emergency_pauses(storage: &dyn Storage, vote: &mut VoteStatus) -> Result<bool, ContractError> {
+    let pauses = range_emergency_pauses(storage, vote.emergency_pauses_seen)
+        .collect::<StdResult<Vec<_>>>()?;
+    let changed = !pauses.is_empty();
+    for pause in pauses {
+        vote.emergency_pauses_seen = pause.number;
+        if vote.paused || vote.outcome.is_some() || vote.cancel_reason.is_some() {
+            continue;
+        }
+        let frozen = match vote.expires {
+            Expiration::AtHeight(end) if end > pause.started.height => pause.blocks,
+            Expiration::AtTime(end) if end > pause.started.time => pause.seconds,
+            _ => continue,
+        };
+        let frozen = frozen.min(pause_cap_left(vote));
+        vote.expires = extend_expiration(&vote.expires, frozen)?;
+        vote.pause_extension += frozen;
+    }
+    Ok(changed)
+}
+
+fn tally_for<'a>(vote: &'a mut VoteStatus, option: &str) -> Result<&'a mut Uint128, ContractError> {
+    match option {
+        "For" => Ok(&mut vote.votes_for),
+        "Against" => Ok(&mut vote.votes_against),
+        "Abstain" => Ok(&mut vote.votes_abstain),
+        "NoWithVeto" => Ok(&mut vote.votes_no_with_veto),
+        _ => Err(ContractError::VoteNotValid {}),
+    }
+}
+
+pub fn add_to_tally(vote: &mut VoteStatus, option: &str, power: Uint128) -> Result<(), ContractError> {
+    let counter = tally_for(vote, option)?;
+    *counter = counter.checked_add(power)?;
+    Ok(())
+}
+
+pub fn remove_from_tally(vote: &mut VoteStatus, option: &str, power: Uint128) -> Result<(), ContractError> {
+    let counter = tally_for(vote, option)?;
+    *counter = counter.checked_sub(power)?;
+    Ok(())
+}
@@ -1,12 +1,58 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod test {
 
-    use cosmwasm_std::testing::{mock_dependencies_with_balances, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary};
+    use cosmwasm_std::testing::{mock_dependencies_with_balances, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coin, coins, from_binary, to_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal, Empty, Order, OwnedDeps, Pair, Reply,
+        Storage, SubMsg, Uint128, Uint64};
+    use std::cell::Cell;
     use governance_types::errors::ContractError;
-    use governance_types::types::{InstantiateMsg, QueryMsg, ExecuteMsg};
-    use crate::state::{Config, VoteStatus, Stats};
-    use crate::contract::{execute, instantiate, query};
+    use governance_types::events::{parse_events, GovernanceEvent, TallyAttributes};
+    use governance_types::hooks::{GovernanceHookMsg, HookPolicy};
+    use governance_types::types::{InstantiateMsg, QueryMsg, ExecuteMsg, MigrateMsg, Outcome, DelegatorsResponse,
+        ProposalCategory, CategoryField, VoteOverrides, Threshold, ProposalListResponse, ProposalStatus,
+        BallotResponse, BallotListResponse, ConfigResponse, ProposalResponse, StatsResponse, TallyResponse,
+        ExtendedStatsResponse, CreatorStatsResponse, ProposalBreakdownResponse, VerifyStatsResponse, StatsSnapshot, StatsHistoryResponse,
+        HooksResponse, RewardAsset, RewardPoolResponse, PendingRewardsResponse, ReceiveMsg, RefundPolicy,
+        ProposalUpdate, ProposalRevision, ProposalRevisionsResponse, ProposalMetadata,
+        EmergencyPauseState, LateQuorum};
+    use cw0::{Duration, Expiration};
+    use cw20::Cw20ReceiveMsg;
+    use cw2::{get_contract_version, set_contract_version};
+    use crate::contract::{execute, instantiate, migrate, query, reply};
+    use crate::hooks::HOOK_REPLY_ID;
+    use crate::delegation::{MAX_DELEGATION_DEPTH, MAX_DELEGATION_TREE};
+    use crate::assert::MAX_LATE_QUORUM_BLOCKS;
+    use crate::state::{Stats, read_stats, store_stats, range_ended_votes};
+
+    fn set_text_category(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        let msg = ExecuteMsg::SetCategory {
+            name: "text".to_string(),
+            category: ProposalCategory {
+                min_votes_count: Uint128::new(1),
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                veto_threshold: Decimal::percent(34),
+                voting_period: None,
+                deposit: None,
+                cancel_refund: RefundPolicy::Always,
+                max_pause_extension: None,
+                late_quorum: None,
+                whitelist_on: false,
+                whitelist: vec![],
+                required_coins_on: false,
+                required_coin: coin(1, "test"),
+                overridable: vec![
+                    CategoryField::MinVotesCount,
+                    CategoryField::Threshold,
+                    CategoryField::VetoThreshold,
+                    CategoryField::VotingPeriod,
+                    CategoryField::Whitelist,
+                    CategoryField::RequiredCoin,
+                ],
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
 
     #[test]
     fn proper_initialization() {
@@ -21,7 +67,7 @@ mod test {
         assert_eq!(0, res.messages.len());
 
         let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-        let value: Config = from_binary(&res).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
         let info = mock_info("creator", &coins(1000, "earth"));
         assert_eq!(value.admins[0], info.sender);
     }
@@ -33,16 +79,14 @@ mod test {
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        set_text_category(&mut deps);
 
         let info = mock_info("creator", &coins(1000, "earth"));
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: false,
-            whitelist: vec![],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
 
@@ -50,23 +94,23 @@ mod test {
             title: "sdsd".to_string()
         };
         let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
-        let value: Option<VoteStatus> = from_binary(&res).unwrap();
+        let value: Option<ProposalResponse> = from_binary(&res).unwrap();
         assert_eq!(value, None);
 
         let get_vote = QueryMsg::GetVote {
             title: "some title".to_string()
         };
         let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
-        let value: VoteStatus = from_binary(&res).unwrap();
-        assert_eq!(value.whitelist_on, false);
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert!(!value.whitelist_on);
 
-        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
-        let value: Config = from_binary(&res).unwrap();
-        assert_eq!(value.votes_titles, vec!["some title".to_string()]);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVotesTitles {}).unwrap();
+        let value: Vec<String> = from_binary(&res).unwrap();
+        assert_eq!(value, vec!["some title".to_string()]);
 
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
-        let value: Stats = from_binary(&res).unwrap();
-        assert_eq!(value.in_progress, 1);
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(1));
     }
     #[test]
     fn proper_vote() {
@@ -76,15 +120,13 @@ mod test {
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
 
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: false,
-            whitelist: vec![],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
 
@@ -98,12 +140,12 @@ mod test {
             title: "some title".to_string()
         };
         let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
-        let value: VoteStatus = from_binary(&res).unwrap();
-        assert_eq!(value.votes_for, 1);
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_for, Uint128::new(1));
 
         let _res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
-        let value: Stats = from_binary(&_res).unwrap();
-        assert_eq!(value.in_progress, 1);
+        let value: StatsResponse = from_binary(&_res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(1));
     }
     #[test]
     fn proper_pause_and_unpause() {
@@ -113,14 +155,12 @@ mod test {
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: false,
-            whitelist: vec![],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
 
@@ -134,13 +174,13 @@ mod test {
             title: "some title".to_string()
         };
         let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
-        let value: VoteStatus = from_binary(&res).unwrap();
-        assert_eq!(value.paused, true);
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.status, ProposalStatus::Paused);
 
         let _res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
-        let value: Stats = from_binary(&_res).unwrap();
-        assert_eq!(value.in_progress, 0);
-        assert_eq!(value.paused, 1);
+        let value: StatsResponse = from_binary(&_res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(0));
+        assert_eq!(value.paused, Uint64::new(1));
     }
     #[test]
     fn privent_vote_on_pause() {
@@ -150,14 +190,12 @@ mod test {
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: false,
-            whitelist: vec![],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
         };
         let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
 
@@ -186,14 +224,16 @@ mod test {
         let info = mock_info("creator", &coins(1000, "earth"));
         let whitelisted = mock_info("whitelisted", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: true,
-            whitelist: vec![whitelisted.sender],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                whitelist_on: Some(true),
+                whitelist: Some(vec![whitelisted.sender]),
+                ..VoteOverrides::default()
+            },
         };
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
 
@@ -224,14 +264,16 @@ mod test {
         let info = mock_info("creator", &coins(1000, "earth"));
         let whitelisted = mock_info("whitelisted", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: true,
-            whitelist: vec![whitelisted.sender],
-            required_coins_on: false,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                whitelist_on: Some(true),
+                whitelist: Some(vec![whitelisted.sender]),
+                ..VoteOverrides::default()
+            },
         };
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
 
@@ -248,14 +290,15 @@ mod test {
         };
         let info = mock_info("creator", &coins(1000, "earth"));
         let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
         let new_vote = ExecuteMsg::CreateNewVote {
             title: "some title".to_string(),
-            min_votes_count: 1,
-            required_votes_percentage: 1,
-            whitelist_on: false,
-            whitelist: vec![],
-            required_coins_on: true,
-            required_coin: coin(1, "test"),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                required_coins_on: Some(true),
+                ..VoteOverrides::default()
+            },
         };
         let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
 
@@ -284,6 +327,2327 @@ mod test {
             title: "some title".to_string()
         };
         let info = mock_info("user1", &coins(1, "test"));
-        let res = execute(deps.as_mut(), mock_env(), info, vote).unwrap();
+        let _res = execute(deps.as_mut(), mock_env(), info, vote).unwrap();
+    }
+    #[test]
+    fn proper_veto() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(Threshold::AbsoluteCount { weight: 1 }),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "NoWithVeto".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+
+        let get_vote = QueryMsg::GetVote {
+            title: "some title".to_string()
+        };
+        let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_no_with_veto, Uint128::new(1));
+        assert_eq!(value.outcome, Some(Outcome::Vetoed));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(0));
+        assert_eq!(value.vetoed, Uint64::new(1));
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), vote);
+        match res {
+            Err(ContractError::VoteAlreadyFinalized {}) => {}
+            _ => panic!("Must return vote already finalized error"),
+        }
+    }
+    #[test]
+    fn proper_finalize_accepted() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                min_votes_count: Some(Uint128::new(2)),
+                threshold: Some(Threshold::AbsoluteCount { weight: 1 }),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "Abstain".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(0));
+        assert_eq!(value.accepted, Uint64::new(1));
+    }
+    #[test]
+    fn proper_delegation() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(Threshold::AbsoluteCount { weight: 1 }),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
+
+        let delegate = mock_info("delegate", &[]);
+        for delegator in ["user1", "user2"] {
+            let msg = ExecuteMsg::Delegate { to: delegate.sender.clone() };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(delegator, &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::Delegate { to: mock_info("user1", &[]).sender };
+        let res = execute(deps.as_mut(), mock_env(), delegate.clone(), msg);
+        match res {
+            Err(ContractError::DelegationCycle {}) => {}
+            _ => panic!("Must return delegation cycle error"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDelegators { delegate: delegate.sender.clone() }).unwrap();
+        let value: DelegatorsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.delegators.len(), 2);
+        assert_eq!(value.total_power, Uint128::new(3));
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), delegate, vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "Against".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+
+        let get_vote = QueryMsg::GetVote {
+            title: "some title".to_string()
+        };
+        let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_for, Uint128::new(2));
+        assert_eq!(value.votes_against, Uint128::new(1));
+    }
+    #[test]
+    fn proper_delegation_depth_limit() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // chain of 4: user4 -> user3 -> user2 -> user1
+        for i in 2..MAX_DELEGATION_DEPTH {
+            let msg = ExecuteMsg::Delegate { to: mock_info(&format!("user{}", i - 1), &[]).sender };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(&format!("user{}", i), &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::Delegate { to: mock_info("root", &[]).sender };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("leaf", &[]), msg).unwrap();
+        // the root of a subtree cannot bring its delegators below the deepest level
+        let msg = ExecuteMsg::Delegate { to: mock_info("user4", &[]).sender };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("root", &[]), msg.clone());
+        match res {
+            Err(ContractError::DelegationTooDeep {}) => {}
+            _ => panic!("Must return delegation too deep error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user5", &[]), msg).unwrap();
+    }
+    #[test]
+    fn proper_delegation_tree_limit() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let hub = mock_info("hub", &[]).sender;
+        for i in 1..MAX_DELEGATION_TREE {
+            let msg = ExecuteMsg::Delegate { to: hub.clone() };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(&format!("user{}", i), &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::Delegate { to: hub.clone() };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("outsider", &[]), msg);
+        match res {
+            Err(ContractError::DelegationTreeTooLarge {}) => {}
+            _ => panic!("Must return delegation tree too large error"),
+        }
+        // a full tree can still be reshaped from within
+        let msg = ExecuteMsg::Delegate { to: mock_info("user1", &[]).sender };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Undelegate {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user3", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Delegate { to: hub };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("outsider", &[]), msg).unwrap();
+    }
+    #[test]
+    fn proper_category_overrides_and_deposit() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetCategory {
+            name: "treasury-spend".to_string(),
+            category: ProposalCategory {
+                min_votes_count: Uint128::new(1),
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                veto_threshold: Decimal::percent(34),
+                voting_period: None,
+                deposit: Some(coin(10, "earth")),
+                cancel_refund: RefundPolicy::BeforeVotes,
+                max_pause_extension: None,
+                late_quorum: None,
+                whitelist_on: false,
+                whitelist: vec![],
+                required_coins_on: false,
+                required_coin: coin(1, "test"),
+                overridable: vec![],
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), msg.clone());
+        match _res {
+            Err(ContractError::SenderIsNotAdmin {}) => {}
+            _ => panic!("Must return sender is not admin error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "treasury-spend".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                min_votes_count: Some(Uint128::new(5)),
+                ..VoteOverrides::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(10, "earth")), new_vote);
+        match res {
+            Err(ContractError::FieldNotOverridable {}) => {}
+            _ => panic!("Must return field not overridable error"),
+        }
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "treasury-spend".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote.clone());
+        match res {
+            Err(ContractError::InsufficientDeposit {}) => {}
+            _ => panic!("Must return insufficient deposit error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(10, "earth")), new_vote).unwrap();
+
+        let vote = ExecuteMsg::Vote {
+            vote: "NoWithVeto".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Burn { amount: coins(10, "earth") }));
+    }
+    #[test]
+    fn proper_threshold_quorum() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(Threshold::AbsolutePercentage { percentage: Decimal::zero() }),
+                ..VoteOverrides::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote);
+        match res {
+            Err(ContractError::WrongVotesPercentage {}) => {}
+            _ => panic!("Must return wrong votes percentage error"),
+        }
+        let threshold = Threshold::ThresholdQuorum {
+            threshold: Decimal::from_ratio(6667u128, 10000u128),
+            quorum: Decimal::percent(50),
+        };
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(threshold.clone()),
+                ..VoteOverrides::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote);
+        match res {
+            Err(ContractError::UnknownTotalPower {}) => {}
+            _ => panic!("Must return unknown total power error"),
+        }
+        let voters = ["user1", "user2", "user3", "user4"];
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(threshold),
+                whitelist_on: Some(true),
+                whitelist: Some(voters.iter().map(|voter| mock_info(voter, &[]).sender).collect()),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        for (voter, choice) in [("user1", "For"), ("user2", "For"), ("user3", "Against")] {
+            let vote = ExecuteMsg::Vote {
+                vote: choice.to_string(),
+                title: "some title".to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+
+        // 2 of 3 is just below 66.67%
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.rejected_votes, Uint64::new(1));
+    }
+    #[test]
+    fn proper_migrate_counters() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        // storage written by the first release, before cw2 versioning
+        let legacy_config = r#"{"owner":"creator","admins":[],"votes_titles":["a"]}"#;
+        let legacy_stats = r#"{"in_progress":2,"rejected_votes":1,"accepted":0,"not_resolved":0,"paused":-1}"#;
+        let legacy_vote = r#"{"paused":false,"votes_for":1,"votes_against":0,"votes_abstain":0,"creator":"creator","required_coins_on":false,"required_coins":{"denom":"test","amount":"1"},"min_votes_count":2,"required_votes_percentage":50,"already_participate":["user1"],"whitelist_on":false,"whitelist":[]}"#;
+        deps.storage.set(b"\x00\x06config", legacy_config.as_bytes());
+        deps.storage.set(b"\x00\x06stats", legacy_stats.as_bytes());
+        deps.storage.set(b"\x00\x05VOTESa", legacy_vote.as_bytes());
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(2));
+        assert_eq!(value.rejected_votes, Uint64::new(1));
+        assert_eq!(value.paused, Uint64::zero());
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "a".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_for, Uint128::new(1));
+        assert_eq!(value.min_votes_count, Uint128::new(2));
+        assert_eq!(value.threshold, Threshold::AbsolutePercentage { percentage: Decimal::percent(50) });
+        assert_eq!(value.category, "legacy");
+        assert_eq!(value.expires, Expiration::Never {});
+
+        // voters of the first release keep their single vote
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote.clone());
+        assert_eq!(res.unwrap_err(), ContractError::VoterAlreadyParticipate {});
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), vote).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "a".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_for, Uint128::new(2));
+    }
+    #[test]
+    fn proper_list_proposals() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        for title in ["a", "b", "c"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let pause = ExecuteMsg::Pause {
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, pause).unwrap();
+
+        let list = QueryMsg::ListProposals { start_after: None, limit: Some(2), status: None, creator: None };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        let titles: Vec<String> = value.proposals.into_iter().map(|p| p.title).collect();
+        assert_eq!(titles, vec!["a".to_string(), "b".to_string()]);
+
+        let list = QueryMsg::ListProposals { start_after: Some("b".to_string()), limit: None, status: None, creator: None };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].title, "c");
+
+        let list = QueryMsg::ListProposals { start_after: None, limit: None, status: Some(ProposalStatus::Paused), creator: None };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].title, "b");
+
+        let list = QueryMsg::ReverseProposals { start_before: None, limit: Some(1), status: None, creator: Some(mock_info("creator", &[]).sender) };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].title, "c");
+    }
+    #[test]
+    fn proper_ballot_queries() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        for title in ["a", "bb"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        for (voter, title, choice) in [("user1", "a", "For"), ("user1", "bb", "Against"), ("user2", "a", "Abstain")] {
+            let vote = ExecuteMsg::Vote {
+                vote: choice.to_string(),
+                title: title.to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), vote).unwrap();
+        }
+        let user1 = mock_info("user1", &[]).sender;
+
+        let get_ballot = QueryMsg::GetBallot { title: "bb".to_string(), voter: user1.clone() };
+        let res = query(deps.as_ref(), mock_env(), get_ballot).unwrap();
+        let value: BallotResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ballot.unwrap().vote, "Against");
+
+        let list = QueryMsg::ListVotes { title: "a".to_string(), start_after: None, limit: None };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: BallotListResponse = from_binary(&res).unwrap();
+        let voters: Vec<String> = value.ballots.into_iter().map(|b| b.voter.to_string()).collect();
+        assert_eq!(voters, vec!["user1".to_string(), "user2".to_string()]);
+
+        let history = QueryMsg::VoterHistory { voter: user1.clone(), start_after: None, limit: None };
+        let res = query(deps.as_ref(), mock_env(), history).unwrap();
+        let value: BallotListResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ballots.len(), 2);
+
+        let history = QueryMsg::VoterHistory { voter: user1, start_after: Some("a".to_string()), limit: None };
+        let res = query(deps.as_ref(), mock_env(), history).unwrap();
+        let value: BallotListResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ballots.len(), 1);
+        assert_eq!(value.ballots[0].title, "bb");
+        assert_eq!(value.ballots[0].vote, "Against");
+    }
+    #[test]
+    fn proper_tally_preview() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let voters = ["user1", "user2", "user3", "user4"];
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(Threshold::ThresholdQuorum {
+                    threshold: Decimal::percent(50),
+                    quorum: Decimal::percent(50),
+                }),
+                voting_period: Some(Duration::Time(100)),
+                whitelist_on: Some(true),
+                whitelist: Some(voters.iter().map(|voter| mock_info(voter, &[]).sender).collect()),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+
+        let tally = QueryMsg::Tally { title: "some title".to_string() };
+        let res = query(deps.as_ref(), mock_env(), tally.clone()).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.quorum_reached, Some(Decimal::percent(25)));
+        assert_eq!(value.threshold_reached, Decimal::one());
+        assert_eq!(value.remaining, Some(Duration::Time(100)));
+        assert_eq!(value.projected_outcome, Some(Outcome::NotResolved));
+
+        let vote = ExecuteMsg::Vote {
+            vote: "Against".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), vote).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(40);
+        let res = query(deps.as_ref(), env, tally.clone()).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.remaining, Some(Duration::Time(60)));
+        assert_eq!(value.projected_outcome, Some(Outcome::Accepted));
+
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), finalize.clone());
+        match res {
+            Err(ContractError::VotingPeriodNotOver {}) => {}
+            _ => panic!("Must return voting period not over error"),
+        }
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let _res = execute(deps.as_mut(), env, info.clone(), finalize).unwrap();
+        let get_vote = QueryMsg::GetVote {
+            title: "some title".to_string()
+        };
+        let res = query(deps.as_ref(), mock_env(), get_vote).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.outcome, Some(Outcome::Accepted));
+        let res = query(deps.as_ref(), mock_env(), tally).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.projected_outcome, Some(Outcome::Accepted));
+
+        // a count threshold is measured against its weight
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "count".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                threshold: Some(Threshold::AbsoluteCount { weight: 4 }),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "count".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let tally = QueryMsg::Tally { title: "count".to_string() };
+        let res = query(deps.as_ref(), mock_env(), tally.clone()).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.threshold_reached, Decimal::percent(25));
+        assert_eq!(value.projected_outcome, Some(Outcome::Rejected));
+        let cancel = ExecuteMsg::Cancel {
+            title: "count".to_string(),
+            reason: "superseded".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, cancel).unwrap();
+        let res = query(deps.as_ref(), mock_env(), tally).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.projected_outcome, None);
+    }
+    #[test]
+    fn proper_extended_stats() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "b".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                whitelist_on: Some(true),
+                whitelist: Some(vec![mock_info("user1", &[]).sender, mock_info("user2", &[]).sender]),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        for (voter, title, choice) in [("user1", "a", "For"), ("user2", "a", "Against"), ("user3", "a", "Abstain"), ("user1", "b", "For")] {
+            let vote = ExecuteMsg::Vote {
+                vote: choice.to_string(),
+                title: title.to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), vote).unwrap();
+        }
+
+        let breakdown = QueryMsg::GetProposalBreakdown { title: "a".to_string() };
+        let res = query(deps.as_ref(), mock_env(), breakdown).unwrap();
+        let value: ProposalBreakdownResponse = from_binary(&res).unwrap();
+        assert_eq!(value.total_votes, Uint128::new(3));
+        assert_eq!(value.for_share, Decimal::from_ratio(1u128, 3u128));
+        assert_eq!(value.ballots, 3);
+
+        for title in ["a", "b"] {
+            let finalize = ExecuteMsg::Finalize {
+                title: title.to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), finalize).unwrap();
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetExtendedStats {}).unwrap();
+        let value: ExtendedStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.votes_for, Uint128::new(2));
+        assert_eq!(value.votes_against, Uint128::new(1));
+        assert_eq!(value.votes_abstain, Uint128::new(1));
+        assert_eq!(value.ballots, Uint64::new(4));
+        assert_eq!(value.finalized, Uint64::new(2));
+        assert_eq!(value.average_turnout, Decimal::from_ratio(4u128, 2u128));
+        assert_eq!(value.participation_rate, Some(Decimal::percent(50)));
+
+        let creator_stats = QueryMsg::GetCreatorStats { creator: info.sender };
+        let res = query(deps.as_ref(), mock_env(), creator_stats).unwrap();
+        let value: CreatorStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.created, Uint64::new(2));
+        assert_eq!(value.accepted, Uint64::new(2));
+        assert_eq!(value.average_turnout, Decimal::from_ratio(4u128, 2u128));
+    }
+    #[test]
+    fn proper_recompute_stats() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        for title in ["a", "b", "c"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let pause = ExecuteMsg::Pause {
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), pause).unwrap();
+
+        let mut stats = read_stats(&deps.storage).unwrap();
+        stats.in_progress = Uint64::new(7);
+        store_stats(&mut deps.storage, &stats).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::VerifyStats {}).unwrap();
+        let value: VerifyStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.height, None);
+        assert!(value.consistent);
+
+        let recompute = ExecuteMsg::RecomputeStats { limit: Some(2) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), recompute.clone());
+        match res {
+            Err(ContractError::SenderIsNotAdmin {}) => {}
+            _ => panic!("Must return sender is not admin error"),
+        }
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), recompute.clone()).unwrap();
+        assert_eq!(res.attributes[2].value, "false");
+        // proposals are frozen until the recompute is done
+        let unpause = ExecuteMsg::Unpause {
+            title: "b".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), unpause);
+        match res {
+            Err(ContractError::StatsRecomputeInProgress {}) => {}
+            _ => panic!("Must return stats recompute in progress error"),
+        }
+        let res = execute(deps.as_mut(), mock_env(), info, recompute).unwrap();
+        assert_eq!(res.attributes[2].value, "true");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::VerifyStats {}).unwrap();
+        let value: VerifyStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.height, Some(mock_env().block.height));
+        assert!(!value.consistent);
+        assert_eq!(value.mismatches.len(), 1);
+        assert_eq!(value.mismatches[0].field, "in_progress");
+        assert_eq!(value.mismatches[0].stored, Uint128::new(7));
+        assert_eq!(value.mismatches[0].recomputed, Uint128::new(2));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(2));
+        assert_eq!(value.paused, Uint64::new(1));
+    }
+
+    #[test]
+    fn proper_stats_history() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let mut env = mock_env();
+        let start = env.block.height;
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+
+        for title in ["a", "b"] {
+            env.block.height += 10;
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), new_vote).unwrap();
+        }
+        // stats did not change, no snapshot is written
+        env.block.height += 10;
+        let delegate = ExecuteMsg::Delegate { to: mock_info("user2", &[]).sender };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("user3", &[]), delegate).unwrap();
+        env.block.height += 10;
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), vote).unwrap();
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, finalize).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StatsAt { height: start - 1 }).unwrap();
+        let value: Option<StatsSnapshot> = from_binary(&res).unwrap();
+        assert_eq!(value, None);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StatsAt { height: start + 15 }).unwrap();
+        let value: Option<StatsSnapshot> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(value.height, start + 10);
+        assert_eq!(value.stats.in_progress, Uint64::new(1));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StatsHistory { start_after: None, limit: Some(2) }).unwrap();
+        let value: StatsHistoryResponse = from_binary(&res).unwrap();
+        let heights: Vec<u64> = value.snapshots.iter().map(|snapshot| snapshot.height).collect();
+        assert_eq!(heights, vec![start, start + 10]);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::StatsHistory { start_after: Some(start + 10), limit: None }).unwrap();
+        let value: StatsHistoryResponse = from_binary(&res).unwrap();
+        let heights: Vec<u64> = value.snapshots.iter().map(|snapshot| snapshot.height).collect();
+        assert_eq!(heights, vec![start + 20, start + 40]);
+        assert_eq!(value.snapshots[1].stats.in_progress, Uint64::new(1));
+        assert_eq!(value.snapshots[1].stats.accepted, Uint64::new(1));
+    }
+
+    #[test]
+    fn proper_events() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        assert_eq!(res.events[0].ty, "proposal_created");
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::ProposalCreated {
+            title: "a".to_string(),
+            creator: info.sender.clone(),
+            category: "text".to_string(),
+        }]);
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let voter = mock_info("user1", &[]);
+        let res = execute(deps.as_mut(), mock_env(), voter.clone(), vote).unwrap();
+        let tally = TallyAttributes {
+            votes_for: Uint128::new(1),
+            votes_against: Uint128::zero(),
+            votes_abstain: Uint128::zero(),
+            votes_no_with_veto: Uint128::zero(),
+        };
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::VoteCast {
+            title: "a".to_string(),
+            voter: voter.sender,
+            vote: "For".to_string(),
+            power: Uint128::new(1),
+            tally: tally.clone(),
+        }]);
+
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+        // events come back from the chain with the wasm- prefix
+        let mut events = res.events.clone();
+        events[0].ty = format!("wasm-{}", events[0].ty);
+        events.push(cosmwasm_std::Event::new("transfer").add_attribute("amount", "1token"));
+        assert_eq!(parse_events(&events).unwrap(), vec![GovernanceEvent::ProposalFinalized {
+            title: "a".to_string(),
+            outcome: Outcome::Accepted,
+            tally,
+        }]);
+        let broken = cosmwasm_std::Event::new("vote_cast").add_attribute("title", "a");
+        assert!(GovernanceEvent::parse(&broken).is_err());
+    }
+
+    #[test]
+    fn proper_hooks() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+
+        let add = ExecuteMsg::AddHook {
+            addr: mock_info("rewards", &[]).sender,
+            policy: HookPolicy::Revert,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), add.clone());
+        match res {
+            Err(ContractError::SenderIsNotAdmin {}) => {}
+            _ => panic!("Must return sender is not admin error"),
+        }
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), add.clone()).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::HookAdded {
+            addr: mock_info("rewards", &[]).sender,
+            policy: HookPolicy::Revert,
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), add);
+        match res {
+            Err(ContractError::HookAlreadyRegistered {}) => {}
+            _ => panic!("Must return hook already registered error"),
+        }
+        let add = ExecuteMsg::AddHook {
+            addr: mock_info("indexer", &[]).sender,
+            policy: HookPolicy::Ignore,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), add).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Hooks {}).unwrap();
+        let value: HooksResponse = from_binary(&res).unwrap();
+        assert_eq!(value.hooks.len(), 2);
+        assert_eq!(value.hooks[0].addr, "indexer");
+        assert_eq!(value.hooks[0].policy, HookPolicy::Ignore);
+
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let hook = GovernanceHookMsg::ProposalCreated {
+            title: "a".to_string(),
+            creator: info.sender.clone(),
+            category: "text".to_string(),
+        };
+        assert_eq!(res.messages, vec![
+            SubMsg::reply_on_error(hook.clone().into_cosmos_msg("indexer").unwrap(), HOOK_REPLY_ID),
+            SubMsg::new(hook.into_cosmos_msg("rewards").unwrap()),
+        ]);
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let hook = GovernanceHookMsg::VoteCast {
+            title: "a".to_string(),
+            voter: mock_info("user1", &[]).sender,
+            vote: "For".to_string(),
+            power: Uint128::new(1),
+        };
+        assert_eq!(res.messages[1], SubMsg::new(hook.into_cosmos_msg("rewards").unwrap()));
+
+        let remove = ExecuteMsg::RemoveHook { addr: mock_info("rewards", &[]).sender };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), remove.clone()).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::HookRemoved {
+            addr: mock_info("rewards", &[]).sender,
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), remove);
+        match res {
+            Err(ContractError::HookNotRegistered {}) => {}
+            _ => panic!("Must return hook not registered error"),
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+        let hook = GovernanceHookMsg::ProposalFinalized {
+            title: "a".to_string(),
+            outcome: Outcome::Accepted,
+        };
+        assert_eq!(res.messages, vec![SubMsg::reply_on_error(hook.into_cosmos_msg("indexer").unwrap(), HOOK_REPLY_ID)]);
+
+        // failure of an ignored hook is swallowed by the reply handler
+        let failed = Reply {
+            id: HOOK_REPLY_ID,
+            result: ContractResult::Err("out of funds".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), failed).unwrap();
+        assert_eq!(res.attributes[1].value, "out of funds");
+        let unknown = Reply {
+            id: 42,
+            result: ContractResult::Err("error".to_string()),
+        };
+        let res = reply(deps.as_mut(), mock_env(), unknown);
+        match res {
+            Err(ContractError::UnknownReplyId {}) => {}
+            _ => panic!("Must return unknown reply id error"),
+        }
+    }
+
+    #[test]
+    fn proper_rewards() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+
+        let fund = ExecuteMsg::FundRewards {};
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(250, "ureward")), fund.clone());
+        match res {
+            Err(ContractError::RewardPoolNotConfigured {}) => {}
+            _ => panic!("Must return reward pool not configured error"),
+        }
+        let set_pool = ExecuteMsg::SetRewardPool {
+            asset: RewardAsset::Native { denom: "ureward".to_string() },
+            per_proposal: Uint128::new(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), set_pool).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::RewardPoolSet {
+            asset: RewardAsset::Native { denom: "ureward".to_string() },
+            per_proposal: Uint128::new(100),
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(250, "ureward")), fund.clone()).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::RewardsFunded {
+            sender: mock_info("sponsor", &[]).sender,
+            amount: Uint128::new(250),
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(250, "uother")), fund);
+        match res {
+            Err(ContractError::InvalidRewardFunds {}) => {}
+            _ => panic!("Must return invalid reward funds error"),
+        }
+        // the pool holds native coins, cw20 tokens are refused
+        let receive = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "sponsor".to_string(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::FundRewards {}).unwrap(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("token", &[]), receive);
+        match res {
+            Err(ContractError::InvalidRewardFunds {}) => {}
+            _ => panic!("Must return invalid reward funds error"),
+        }
+        let set_pool = ExecuteMsg::SetRewardPool {
+            asset: RewardAsset::Cw20 { contract_addr: mock_info("token", &[]).sender },
+            per_proposal: Uint128::new(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), set_pool);
+        match res {
+            Err(ContractError::RewardAssetLocked {}) => {}
+            _ => panic!("Must return reward asset locked error"),
+        }
+
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let delegate = ExecuteMsg::Delegate { to: mock_info("user1", &[]).sender };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), delegate).unwrap();
+        for (voter, option) in [("user1", "For"), ("user3", "Against")] {
+            let vote = ExecuteMsg::Vote {
+                vote: option.to_string(),
+                title: "a".to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingRewards { address: mock_info("user1", &[]).sender, start_after: None, limit: None }).unwrap();
+        let value: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Uint128::new(66));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingRewards { address: mock_info("user3", &[]).sender, start_after: None, limit: None }).unwrap();
+        let value: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Uint128::new(33));
+        // the allotment rounds down, the rest stays in the balance
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardPool {}).unwrap();
+        let value: Option<RewardPoolResponse> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(value.balance, Uint128::new(151));
+        assert_eq!(value.pending, Uint128::new(99));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), ExecuteMsg::ClaimRewards { start_after: None, limit: None }).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: coins(66, "ureward"),
+        }));
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::RewardsClaimed {
+            voter: mock_info("user1", &[]).sender,
+            amount: Uint128::new(66),
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), ExecuteMsg::ClaimRewards { start_after: None, limit: None });
+        match res {
+            Err(ContractError::NoRewardsToClaim {}) => {}
+            _ => panic!("Must return no rewards to claim error"),
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardPool {}).unwrap();
+        let value: Option<RewardPoolResponse> = from_binary(&res).unwrap();
+        assert_eq!(value.unwrap().pending, Uint128::new(33));
+
+        // later proposals add to the claimable rewards, claimed ballots are not paid twice
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "b".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), new_vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let finalize = ExecuteMsg::Finalize {
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), finalize).unwrap();
+        // one ballot per page, the claimed ballot of "a" fills the first one
+        let pending = |start_after: Option<&str>| QueryMsg::PendingRewards {
+            address: mock_info("user1", &[]).sender,
+            start_after: start_after.map(String::from),
+            limit: Some(1),
+        };
+        let res = query(deps.as_ref(), mock_env(), pending(None)).unwrap();
+        let value: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Uint128::zero());
+        assert_eq!(value.last_title, Some("a".to_string()));
+        let res = query(deps.as_ref(), mock_env(), pending(Some("a"))).unwrap();
+        let value: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Uint128::new(100));
+        assert_eq!(value.last_title, Some("b".to_string()));
+        let res = query(deps.as_ref(), mock_env(), pending(Some("b"))).unwrap();
+        let value: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.amount, Uint128::zero());
+        assert_eq!(value.last_title, None);
+        let claim = ExecuteMsg::ClaimRewards { start_after: None, limit: Some(1) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), claim);
+        match res {
+            Err(ContractError::NoRewardsToClaim {}) => {}
+            _ => panic!("Must return no rewards to claim error"),
+        }
+        let claim = ExecuteMsg::ClaimRewards { start_after: Some("a".to_string()), limit: Some(1) };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), claim).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: coins(100, "ureward"),
+        }));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user3", &[]), ExecuteMsg::ClaimRewards { start_after: None, limit: None }).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user3".to_string(),
+            amount: coins(33, "ureward"),
+        }));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardPool {}).unwrap();
+        let value: Option<RewardPoolResponse> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(value.balance, Uint128::new(51));
+        assert_eq!(value.pending, Uint128::zero());
+    }
+
+    #[test]
+    fn proper_reward_dust() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let set_pool = ExecuteMsg::SetRewardPool {
+            asset: RewardAsset::Native { denom: "ureward".to_string() },
+            per_proposal: Uint128::new(7),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), set_pool).unwrap();
+        let fund = ExecuteMsg::FundRewards {};
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("sponsor", &coins(7, "ureward")), fund).unwrap();
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        // user1 votes with a power of 2 and user3 with a power of 3
+        for (delegator, delegate) in [("user2", "user1"), ("user4", "user3"), ("user5", "user3")] {
+            let delegate = ExecuteMsg::Delegate { to: mock_info(delegate, &[]).sender };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(delegator, &[]), delegate).unwrap();
+        }
+        for voter in ["user1", "user3"] {
+            let vote = ExecuteMsg::Vote {
+                vote: "For".to_string(),
+                title: "a".to_string()
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(voter, &[]), vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+
+        // each unit of power earns 1.4, the shares round down to 2 and 4
+        let claim = ExecuteMsg::ClaimRewards { start_after: None, limit: None };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), claim.clone()).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user1".to_string(),
+            amount: coins(2, "ureward"),
+        }));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardPool {}).unwrap();
+        let value: Option<RewardPoolResponse> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(value.balance, Uint128::zero());
+        assert_eq!(value.pending, Uint128::new(5));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user3", &[]), claim).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user3".to_string(),
+            amount: coins(4, "ureward"),
+        }));
+        // the last claim returns the dust to the balance
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardPool {}).unwrap();
+        let value: Option<RewardPoolResponse> = from_binary(&res).unwrap();
+        let value = value.unwrap();
+        assert_eq!(value.balance, Uint128::new(1));
+        assert_eq!(value.pending, Uint128::zero());
+    }
+
+    #[test]
+    fn proper_migrate_versions() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.contract, "crates.io:governance-contract");
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
+
+        set_contract_version(&mut deps.storage, "crates.io:governance-contract", "99.0.0").unwrap();
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default());
+        match res {
+            Err(ContractError::CannotDowngrade {}) => {}
+            _ => panic!("Must return cannot downgrade error"),
+        }
+        set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.0.1").unwrap();
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default());
+        match res {
+            Err(ContractError::WrongContractName {}) => {}
+            _ => panic!("Must return wrong contract name error"),
+        }
+
+        // versioned deployments keep their uint counters, stats can be rebuilt on the way
+        set_contract_version(&mut deps.storage, "crates.io:governance-contract", "0.0.0").unwrap();
+        store_stats(&mut deps.storage, &Stats::default()).unwrap();
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { rebuild_stats: true }).unwrap();
+        assert_eq!(res.attributes[1].value, "0.0.0");
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(1));
+    }
+
+    // Counts the bytes read and written, as a proxy for the gas used by an execution.
+    #[derive(Default)]
+    struct CountingStorage {
+        inner: MockStorage,
+        bytes: Cell<usize>,
+    }
+    impl CountingStorage {
+        fn count(&self, key: &[u8], value: &[u8]) {
+            self.bytes.set(self.bytes.get() + key.len() + value.len());
+        }
+    }
+    impl Storage for CountingStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            let value = self.inner.get(key);
+            self.count(key, value.as_deref().unwrap_or_default());
+            value
+        }
+        fn range<'a>(&'a self, start: Option<&[u8]>, end: Option<&[u8]>, order: Order) -> Box<dyn Iterator<Item = Pair> + 'a> {
+            Box::new(self.inner.range(start, end, order).inspect(move |(key, value)| self.count(key, value)))
+        }
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.count(key, value);
+            self.inner.set(key, value)
+        }
+        fn remove(&mut self, key: &[u8]) {
+            self.count(key, &[]);
+            self.inner.remove(key)
+        }
+    }
+
+    #[test]
+    fn vote_cost_does_not_grow_with_voters() {
+        let mut deps = OwnedDeps {
+            storage: CountingStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::<Empty>::new(&[]),
+        };
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let category = ExecuteMsg::SetCategory {
+            name: "text".to_string(),
+            category: ProposalCategory {
+                min_votes_count: Uint128::new(1),
+                threshold: Threshold::AbsoluteCount { weight: 1 },
+                veto_threshold: Decimal::percent(34),
+                voting_period: None,
+                deposit: None,
+                cancel_refund: RefundPolicy::Always,
+                max_pause_extension: None,
+                late_quorum: None,
+                whitelist_on: false,
+                whitelist: vec![],
+                required_coins_on: false,
+                required_coin: coin(0, "token"),
+                overridable: vec![],
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), category).unwrap();
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
+
+        let mut costs = vec![];
+        for i in 1..=10_000 {
+            let vote = ExecuteMsg::Vote {
+                vote: "For".to_string(),
+                title: "a".to_string()
+            };
+            deps.storage.bytes.set(0);
+            let voter = format!("voter{:05}", i);
+            let _res = execute(deps.as_mut(), mock_env(), mock_info(&voter, &[]), vote).unwrap();
+            if [10, 100, 1_000, 10_000].contains(&i) {
+                costs.push(deps.storage.bytes.get());
+            }
+        }
+        // only the counters get one digit longer from one step to the next
+        for step in costs.windows(2) {
+            assert!(step[1] <= step[0] + 32, "vote cost grew from {} to {} bytes", step[0], step[1]);
+        }
+    }
+
+    #[test]
+    fn proper_migrate_participants() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "a".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, new_vote).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+
+        // 0.0.1 layout: voters listed inside the vote, user2 voted before ballots were recorded
+        let key = b"\x00\x05VOTESa";
+        let raw = String::from_utf8(deps.storage.get(key).unwrap()).unwrap();
+        let raw = raw.replace(r#""ballots":"1""#, r#""already_participate":["user1","user2"]"#);
+        deps.storage.set(key, raw.as_bytes());
+        set_contract_version(&mut deps.storage, "crates.io:governance-contract", "0.0.1").unwrap();
+
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        let raw = String::from_utf8(deps.storage.get(key).unwrap()).unwrap();
+        assert!(!raw.contains("already_participate"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetProposalBreakdown { title: "a".to_string() }).unwrap();
+        let value: ProposalBreakdownResponse = from_binary(&res).unwrap();
+        assert_eq!(value.ballots, 2);
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), vote);
+        match res {
+            Err(ContractError::VoterAlreadyParticipate {}) => {}
+            _ => panic!("Must return voter already participate error"),
+        }
+    }
+
+    #[test]
+    fn proper_migrate_baseline_to_current() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let legacy_config = r#"{"owner":"creator","admins":[],"votes_titles":["a","b"]}"#;
+        let legacy_stats = r#"{"in_progress":1,"rejected_votes":0,"accepted":0,"not_resolved":0,"paused":1}"#;
+        let legacy_a = r#"{"paused":false,"votes_for":1,"votes_against":1,"votes_abstain":0,"creator":"creator","required_coins_on":false,"required_coins":{"denom":"test","amount":"1"},"min_votes_count":1,"required_votes_percentage":0,"already_participate":["user1","user2"],"whitelist_on":true,"whitelist":["user1","user2","user3"]}"#;
+        let legacy_b = r#"{"paused":true,"votes_for":0,"votes_against":0,"votes_abstain":0,"creator":"admin","required_coins_on":false,"required_coins":{"denom":"test","amount":"1"},"min_votes_count":1,"required_votes_percentage":100,"already_participate":[],"whitelist_on":false,"whitelist":[]}"#;
+        deps.storage.set(b"\x00\x06config", legacy_config.as_bytes());
+        deps.storage.set(b"\x00\x06stats", legacy_stats.as_bytes());
+        deps.storage.set(b"\x00\x05VOTESa", legacy_a.as_bytes());
+        deps.storage.set(b"\x00\x05VOTESb", legacy_b.as_bytes());
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { rebuild_stats: true }).unwrap();
+        assert_eq!(res.attributes[1].value, "none");
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        // every later step ran: ballots, then the proposal indexes
+        let list = QueryMsg::ListVotes { title: "a".to_string(), start_after: None, limit: None };
+        let value: BallotListResponse = from_binary(&query(deps.as_ref(), mock_env(), list).unwrap()).unwrap();
+        assert_eq!(value.ballots.len(), 2);
+        let list = QueryMsg::ListProposals { start_after: None, limit: None, status: Some(ProposalStatus::Paused), creator: None };
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), list).unwrap()).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].title, "b");
+        let list = QueryMsg::ListProposals { start_after: None, limit: None, status: None, creator: Some(Addr::unchecked("creator")) };
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), list).unwrap()).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].title, "a");
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "a".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.total_power, Some(3));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(1));
+        assert_eq!(value.paused, Uint64::new(1));
+        // titles come from the stored proposals, not from the legacy config list
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVotesTitles {}).unwrap();
+        let value: Vec<String> = from_binary(&res).unwrap();
+        assert_eq!(value, vec!["a".to_string(), "b".to_string()]);
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user2", &[]), vote.clone());
+        assert_eq!(res.unwrap_err(), ContractError::VoterAlreadyParticipate {});
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user3", &[]), vote).unwrap();
+    }
+
+    #[test]
+    fn proper_proposal_indexes() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let periods = [("a", Some(Duration::Height(10))), ("b", Some(Duration::Time(60))), ("c", Some(Duration::Height(5))), ("d", None)];
+        for (title, voting_period) in periods {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides { voting_period, ..VoteOverrides::default() },
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "c".to_string()
+        };
+        let mut env = mock_env();
+        env.block.height += 5;
+        let _res = execute(deps.as_mut(), env, info.clone(), finalize).unwrap();
+
+        let list = QueryMsg::ListProposals { start_after: Some("a".to_string()), limit: None, status: Some(ProposalStatus::InProgress), creator: None };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        let titles: Vec<String> = value.proposals.into_iter().map(|p| p.title).collect();
+        assert_eq!(titles, vec!["b".to_string(), "d".to_string()]);
+        let list = QueryMsg::ListProposals { start_after: None, limit: None, status: None, creator: Some(mock_info("user1", &[]).sender) };
+        let res = query(deps.as_ref(), mock_env(), list).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        assert!(value.proposals.is_empty());
+        let list = |status: ProposalStatus, creator: &str| QueryMsg::ListProposals {
+            start_after: None,
+            limit: None,
+            status: Some(status),
+            creator: Some(mock_info(creator, &[]).sender),
+        };
+        let res = query(deps.as_ref(), mock_env(), list(ProposalStatus::NotResolved, "creator")).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        let titles: Vec<String> = value.proposals.into_iter().map(|p| p.title).collect();
+        assert_eq!(titles, vec!["c".to_string()]);
+        let res = query(deps.as_ref(), mock_env(), list(ProposalStatus::InProgress, "user1")).unwrap();
+        let value: ProposalListResponse = from_binary(&res).unwrap();
+        assert!(value.proposals.is_empty());
+
+        // finalized and never ending proposals are left out
+        let mut env = mock_env();
+        env.block.height += 10;
+        let ended: Vec<String> = range_ended_votes(&deps.storage, &env.block)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(ended, vec!["a".to_string()]);
+        env.block.time = env.block.time.plus_seconds(60);
+        let ended: Vec<String> = range_ended_votes(&deps.storage, &env.block)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(ended, vec!["a".to_string(), "b".to_string()]);
+        env.block.height -= 1;
+        let ended: Vec<String> = range_ended_votes(&deps.storage, &env.block)
+            .map(|item| item.unwrap().0)
+            .collect();
+        assert_eq!(ended, vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn proper_finalize_expired() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let periods = [("a", Some(Duration::Height(10))), ("b", Some(Duration::Height(5))), ("c", Some(Duration::Height(5))), ("d", None)];
+        for (title, voting_period) in periods {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides { voting_period, ..VoteOverrides::default() },
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let pause = ExecuteMsg::Pause {
+            title: "c".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, pause).unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let finalize = ExecuteMsg::FinalizeExpired { limit: Some(1) };
+        let keeper = mock_info("keeper", &[]);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), finalize.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::ProposalFinalized {
+            title: "b".to_string(),
+            outcome: Outcome::NotResolved,
+            tally: TallyAttributes {
+                votes_for: Uint128::zero(),
+                votes_against: Uint128::zero(),
+                votes_abstain: Uint128::zero(),
+                votes_no_with_veto: Uint128::zero(),
+            },
+        }]);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), finalize.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+        // the paused proposal and the one without an end are left alone
+        let res = execute(deps.as_mut(), env, keeper, finalize).unwrap();
+        assert_eq!(res.attributes[1].value, "0");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.accepted, Uint64::new(1));
+        assert_eq!(value.not_resolved, Uint64::new(1));
+        assert_eq!(value.paused, Uint64::new(1));
+        assert_eq!(value.in_progress, Uint64::new(1));
+    }
+    #[test]
+    fn proper_cancel() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let mut category = ProposalCategory {
+            min_votes_count: Uint128::new(1),
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            veto_threshold: Decimal::percent(34),
+            voting_period: Some(Duration::Height(10)),
+            deposit: Some(coin(10, "earth")),
+            cancel_refund: RefundPolicy::BeforeVotes,
+            max_pause_extension: None,
+            late_quorum: None,
+            whitelist_on: false,
+            whitelist: vec![],
+            required_coins_on: false,
+            required_coin: coin(1, "test"),
+            overridable: vec![],
+        };
+        let msg = ExecuteMsg::SetCategory { name: "spend".to_string(), category: category.clone() };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        category.cancel_refund = RefundPolicy::Never;
+        let msg = ExecuteMsg::SetCategory { name: "strict".to_string(), category };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        for (title, category) in [("a", "spend"), ("b", "spend"), ("c", "strict"), ("d", "spend")] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: category.to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &coins(10, "earth")), new_vote).unwrap();
+        }
+
+        let cancel = |title: &str| ExecuteMsg::Cancel {
+            title: title.to_string(),
+            reason: "superseded".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), cancel("a"));
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        // no ballots yet, the deposit goes back to the creator
+        let pause = ExecuteMsg::Pause {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), pause).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), cancel("a")).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Send { to_address: "creator".to_string(), amount: coins(10, "earth") }));
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::ProposalCancelled {
+            title: "a".to_string(),
+            sender: Addr::unchecked("creator"),
+            reason: "superseded".to_string(),
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), cancel("a"));
+        match res {
+            Err(ContractError::VoteCancelled {}) => {}
+            _ => panic!("Must return vote cancelled error"),
+        }
+        let unpause = ExecuteMsg::Unpause {
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), unpause);
+        match res {
+            Err(ContractError::VoteCancelled {}) => {}
+            _ => panic!("Must return vote cancelled error"),
+        }
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "a".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote);
+        match res {
+            Err(ContractError::VoteCancelled {}) => {}
+            _ => panic!("Must return vote cancelled error"),
+        }
+
+        // once someone voted the deposit is burned, the same as under the never policy
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), cancel("b")).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Burn { amount: coins(10, "earth") }));
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), cancel("c")).unwrap();
+        assert_eq!(res.messages[0].msg, CosmosMsg::Bank(BankMsg::Burn { amount: coins(10, "earth") }));
+
+        let mut env = mock_env();
+        env.block.height += 10;
+        let res = execute(deps.as_mut(), env, info, cancel("d"));
+        match res {
+            Err(ContractError::VoteExpired {}) => {}
+            _ => panic!("Must return vote expired error"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "a".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.status, ProposalStatus::Cancelled);
+        assert_eq!(value.cancel_reason, Some("superseded".to_string()));
+        let list = QueryMsg::ListProposals { start_after: None, limit: None, status: Some(ProposalStatus::Cancelled), creator: None };
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), list).unwrap()).unwrap();
+        assert_eq!(value.proposals.len(), 3);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.cancelled, Uint64::new(3));
+        assert_eq!(value.paused, Uint64::zero());
+        assert_eq!(value.in_progress, Uint64::new(1));
+        let creator_stats = QueryMsg::GetCreatorStats { creator: Addr::unchecked("creator") };
+        let res = query(deps.as_ref(), mock_env(), creator_stats.clone()).unwrap();
+        let value: CreatorStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.created, Uint64::new(4));
+        assert_eq!(value.cancelled, Uint64::new(3));
+
+        // a recompute rebuilds the creator counters to the same values
+        let recompute = ExecuteMsg::RecomputeStats { limit: None };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), recompute).unwrap();
+        let res = query(deps.as_ref(), mock_env(), creator_stats).unwrap();
+        let rebuilt: CreatorStatsResponse = from_binary(&res).unwrap();
+        assert_eq!(rebuilt, value);
+    }
+    #[test]
+    fn proper_cancel_paused_after_end() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                voting_period: Some(Duration::Height(10)),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        let pause = ExecuteMsg::Pause {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), pause).unwrap();
+
+        // the original end passed while the proposal was paused
+        let mut env = mock_env();
+        env.block.height += 20;
+        let cancel = ExecuteMsg::Cancel {
+            title: "some title".to_string(),
+            reason: "superseded".to_string(),
+        };
+        let _res = execute(deps.as_mut(), env, info, cancel).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "some title".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.status, ProposalStatus::Cancelled);
+    }
+    #[test]
+    fn proper_update_proposal() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        let update = ExecuteMsg::UpdateProposal {
+            title: "some title".to_string(),
+            update: ProposalUpdate {
+                description: Some("fixed typo".to_string()),
+                min_votes_count: Some(Uint128::new(3)),
+                ..ProposalUpdate::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), update.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return unauthorized error"),
+        }
+        let empty = ExecuteMsg::UpdateProposal {
+            title: "some title".to_string(),
+            update: ProposalUpdate::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), empty);
+        match res {
+            Err(ContractError::NothingToUpdate {}) => {}
+            _ => panic!("Must return nothing to update error"),
+        }
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::ProposalUpdated {
+            title: "some title".to_string(),
+            editor: Addr::unchecked("creator"),
+            revision: 1,
+        }]);
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), vote).unwrap();
+        // once voting started only the metadata can change
+        let update = ExecuteMsg::UpdateProposal {
+            title: "some title".to_string(),
+            update: ProposalUpdate {
+                whitelist_on: Some(true),
+                ..ProposalUpdate::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update);
+        match res {
+            Err(ContractError::VoteAlreadyStarted {}) => {}
+            _ => panic!("Must return vote already started error"),
+        }
+        let links = vec!["ipfs://QmProposal".to_string()];
+        let update = ExecuteMsg::UpdateProposal {
+            title: "some title".to_string(),
+            update: ProposalUpdate {
+                links: Some(links.clone()),
+                ..ProposalUpdate::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), update).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "some title".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.description, "fixed typo");
+        assert_eq!(value.links, links);
+        assert_eq!(value.min_votes_count, Uint128::new(3));
+        assert_eq!(value.revision, 2);
+        let revisions = QueryMsg::ProposalRevisions { title: "some title".to_string(), start_after: Some(1), limit: None };
+        let value: ProposalRevisionsResponse = from_binary(&query(deps.as_ref(), mock_env(), revisions).unwrap()).unwrap();
+        assert_eq!(value.revisions, vec![ProposalRevision {
+            revision: 2,
+            editor: Addr::unchecked("creator"),
+            height: mock_env().block.height,
+            update: ProposalUpdate {
+                links: Some(links),
+                ..ProposalUpdate::default()
+            },
+        }]);
+
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), finalize).unwrap();
+        let update = ExecuteMsg::UpdateProposal {
+            title: "some title".to_string(),
+            update: ProposalUpdate {
+                description: Some("too late".to_string()),
+                ..ProposalUpdate::default()
+            },
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, update);
+        match res {
+            Err(ContractError::VoteAlreadyFinalized {}) => {}
+            _ => panic!("Must return vote already finalized error"),
+        }
+    }
+    #[test]
+    fn proper_metadata() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = |title: &str, metadata: ProposalMetadata| ExecuteMsg::CreateNewVote {
+            title: title.to_string(),
+            category: "text".to_string(),
+            overrides: VoteOverrides::default(),
+            metadata,
+        };
+        let invalid = vec![
+            (new_vote(" ", ProposalMetadata::default()), ContractError::EmptyTitle {}),
+            (new_vote(&"a".repeat(129), ProposalMetadata::default()), ContractError::TitleTooLong {}),
+            (new_vote("some\ntitle", ProposalMetadata::default()), ContractError::InvalidCharacters {}),
+            (new_vote("a", ProposalMetadata { description: "b".repeat(4097), ..ProposalMetadata::default() }), ContractError::DescriptionTooLong {}),
+            (new_vote("a", ProposalMetadata { description: "bell\u{7}".to_string(), ..ProposalMetadata::default() }), ContractError::InvalidCharacters {}),
+            (new_vote("a", ProposalMetadata { links: vec!["ipfs://Qm".to_string(); 11], ..ProposalMetadata::default() }), ContractError::TooManyLinks {}),
+            (new_vote("a", ProposalMetadata { links: vec!["see forum".to_string()], ..ProposalMetadata::default() }), ContractError::InvalidLink {}),
+            (new_vote("a", ProposalMetadata { tags: vec!["Treasury".to_string()], ..ProposalMetadata::default() }), ContractError::InvalidTag {}),
+            (new_vote("a", ProposalMetadata { tags: vec!["dao".to_string(), "dao".to_string()], ..ProposalMetadata::default() }), ContractError::InvalidTag {}),
+        ];
+        for (msg, error) in invalid {
+            let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+            assert_eq!(res.unwrap_err(), error);
+        }
+
+        let metadata = ProposalMetadata {
+            description: "Fund the audit.\nDetails in the linked document.".to_string(),
+            links: vec!["ipfs://QmProposal".to_string(), "https://forum.example/t/1".to_string()],
+            tags: vec!["treasury".to_string(), "security".to_string()],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote("audit", metadata.clone())).unwrap();
+        let other = ProposalMetadata { tags: vec!["treasury".to_string()], ..ProposalMetadata::default() };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote("grants", other)).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "audit".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.description, metadata.description);
+        assert_eq!(value.links, metadata.links);
+        assert_eq!(value.tags, metadata.tags);
+        let by_tag = |tag: &str| QueryMsg::ProposalsByTag { tag: tag.to_string(), start_after: None, limit: None };
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), by_tag("treasury")).unwrap()).unwrap();
+        let titles: Vec<String> = value.proposals.into_iter().map(|proposal| proposal.title).collect();
+        assert_eq!(titles, vec!["audit".to_string(), "grants".to_string()]);
+
+        // retagging moves the proposal between the tag lists
+        let update = ExecuteMsg::UpdateProposal {
+            title: "audit".to_string(),
+            update: ProposalUpdate {
+                tags: Some(vec!["audit".to_string()]),
+                ..ProposalUpdate::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, update).unwrap();
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), by_tag("security")).unwrap()).unwrap();
+        assert!(value.proposals.is_empty());
+        let value: ProposalListResponse = from_binary(&query(deps.as_ref(), mock_env(), by_tag("audit")).unwrap()).unwrap();
+        assert_eq!(value.proposals.len(), 1);
+        assert_eq!(value.proposals[0].tags, vec!["audit".to_string()]);
+    }
+    #[test]
+    fn proper_toogle_whitelist_closed() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        for title in ["a", "b"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), finalize).unwrap();
+        let cancel = ExecuteMsg::Cancel {
+            title: "b".to_string(),
+            reason: "superseded".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), cancel).unwrap();
+
+        let toggle = ExecuteMsg::ToogleWhitelist { title: "a".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), toggle);
+        match res {
+            Err(ContractError::VoteAlreadyFinalized {}) => {}
+            _ => panic!("Must return vote already finalized error"),
+        }
+        let toggle = ExecuteMsg::ToogleWhitelist { title: "b".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), info, toggle);
+        match res {
+            Err(ContractError::VoteCancelled {}) => {}
+            _ => panic!("Must return vote cancelled error"),
+        }
+    }
+    #[test]
+    fn proper_toogle_required_coin_closed() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        for title in ["a", "b"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                metadata: ProposalMetadata::default(),
+                overrides: VoteOverrides::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+        let finalize = ExecuteMsg::Finalize {
+            title: "a".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), finalize).unwrap();
+        let cancel = ExecuteMsg::Cancel {
+            title: "b".to_string(),
+            reason: "superseded".to_string(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), cancel).unwrap();
+
+        let toggle = ExecuteMsg::ToogleRequiredCoin { title: "a".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), toggle);
+        match res {
+            Err(ContractError::VoteAlreadyFinalized {}) => {}
+            _ => panic!("Must return vote already finalized error"),
+        }
+        let toggle = ExecuteMsg::ToogleRequiredCoin { title: "b".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), info, toggle);
+        match res {
+            Err(ContractError::VoteCancelled {}) => {}
+            _ => panic!("Must return vote cancelled error"),
+        }
+    }
+    #[test]
+    fn proper_emergency_pause() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        set_text_category(&mut deps);
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote.clone()).unwrap();
+        let timed_vote = ExecuteMsg::CreateNewVote {
+            title: "timed".to_string(),
+            category: "text".to_string(),
+            metadata: ProposalMetadata::default(),
+            overrides: VoteOverrides {
+                voting_period: Some(Duration::Height(10)),
+                ..VoteOverrides::default()
+            },
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), timed_vote).unwrap();
+
+        let set_guardian = ExecuteMsg::SetGuardian {
+            guardian: Some(Addr::unchecked("guardian")),
+            unpause_delay: Some(Duration::Height(5)),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), set_guardian.clone());
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), set_guardian).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::GuardianSet {
+            guardian: Some(Addr::unchecked("guardian")),
+            unpause_delay: Some(Duration::Height(5)),
+        }]);
+        let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), ExecuteMsg::EmergencyPause {});
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), ExecuteMsg::EmergencyPause {}).unwrap();
+        assert_eq!(parse_events(&res.events).unwrap(), vec![GovernanceEvent::EmergencyPaused {
+            sender: Addr::unchecked("guardian"),
+        }]);
+
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "some title".to_string()
+        };
+        let finalize = ExecuteMsg::Finalize {
+            title: "some title".to_string()
+        };
+        let blocked = vec![
+            (mock_info("user1", &[]), vote.clone()),
+            (info.clone(), new_vote),
+            (info.clone(), finalize),
+            (mock_info("guardian", &[]), ExecuteMsg::EmergencyPause {}),
+        ];
+        for (sender, msg) in blocked {
+            let res = execute(deps.as_mut(), mock_env(), sender, msg);
+            assert_eq!(res.unwrap_err(), ContractError::EmergencyPaused {});
+        }
+
+        // queries keep working and the proposal counters are untouched
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(value.emergency_pause, Some(EmergencyPauseState {
+            paused_by: Addr::unchecked("guardian"),
+            unpause_after: Some(Duration::Height(5).after(&mock_env().block)),
+            paused_at_height: mock_env().block.height,
+            paused_at_time: mock_env().block.time,
+        }));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetStats {}).unwrap();
+        let value: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.in_progress, Uint64::new(2));
+        assert_eq!(value.paused, Uint64::zero());
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("guardian", &[]), ExecuteMsg::EmergencyUnpause {});
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), ExecuteMsg::EmergencyUnpause {});
+        assert_eq!(res.unwrap_err(), ContractError::UnpauseDelayNotElapsed {});
+        let mut env = mock_env();
+        env.block.height += 5;
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnpause {}).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnpause {});
+        assert_eq!(res.unwrap_err(), ContractError::NotEmergencyPaused {});
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("user1", &[]), vote).unwrap();
+
+        // the voting period of the timed proposal moved by the 5 paused blocks
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "timed".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.expires, Expiration::AtHeight(mock_env().block.height + 15));
+        assert_eq!(value.original_expires, Expiration::AtHeight(mock_env().block.height + 10));
+        env.block.height += 5;
+        let finalize = ExecuteMsg::Finalize {
+            title: "timed".to_string()
+        };
+        let res = execute(deps.as_mut(), env, info, finalize);
+        assert_eq!(res.unwrap_err(), ContractError::VotingPeriodNotOver {});
+    }
+    #[test]
+    fn proper_pause_extension() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let mut category = ProposalCategory {
+            min_votes_count: Uint128::new(1),
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            veto_threshold: Decimal::percent(34),
+            voting_period: Some(Duration::Height(10)),
+            deposit: None,
+            cancel_refund: RefundPolicy::Always,
+            max_pause_extension: Some(Duration::Time(5)),
+            late_quorum: None,
+            whitelist_on: false,
+            whitelist: vec![],
+            required_coins_on: false,
+            required_coin: coin(1, "test"),
+            overridable: vec![],
+        };
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category: category.clone() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        assert_eq!(res.unwrap_err(), ContractError::PauseCapUnitMismatch {});
+        category.max_pause_extension = Some(Duration::Height(5));
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            overrides: VoteOverrides::default(),
+            metadata: ProposalMetadata::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        let start = mock_env().block.height;
+        let at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let pause = ExecuteMsg::Pause {
+            title: "some title".to_string()
+        };
+        let unpause = ExecuteMsg::Unpause {
+            title: "some title".to_string()
+        };
+        let _res = execute(deps.as_mut(), at(start + 2), info.clone(), pause.clone()).unwrap();
+        let res = execute(deps.as_mut(), at(start + 5), info.clone(), unpause.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "3");
+        // the second pause lasts longer than what is left of the cap
+        let _res = execute(deps.as_mut(), at(start + 6), info.clone(), pause).unwrap();
+        let res = execute(deps.as_mut(), at(start + 20), info.clone(), unpause).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "some title".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.original_expires, Expiration::AtHeight(start + 10));
+        assert_eq!(value.expires, Expiration::AtHeight(start + 15));
+
+        let finalize = ExecuteMsg::FinalizeExpired { limit: None };
+        let res = execute(deps.as_mut(), at(start + 14), info.clone(), finalize.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "0");
+        let res = execute(deps.as_mut(), at(start + 15), info, finalize).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+    }
+    #[test]
+    fn proper_emergency_pause_cap() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let category = ProposalCategory {
+            min_votes_count: Uint128::new(1),
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            veto_threshold: Decimal::percent(34),
+            voting_period: Some(Duration::Height(10)),
+            deposit: None,
+            cancel_refund: RefundPolicy::Always,
+            max_pause_extension: Some(Duration::Height(3)),
+            late_quorum: None,
+            whitelist_on: false,
+            whitelist: vec![],
+            required_coins_on: false,
+            required_coin: coin(1, "test"),
+            overridable: vec![],
+        };
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let new_vote = ExecuteMsg::CreateNewVote {
+            title: "some title".to_string(),
+            category: "text".to_string(),
+            overrides: VoteOverrides::default(),
+            metadata: ProposalMetadata::default(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+
+        let start = mock_env().block.height;
+        let at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let _res = execute(deps.as_mut(), at(start + 2), info.clone(), ExecuteMsg::EmergencyPause {}).unwrap();
+        let _res = execute(deps.as_mut(), at(start + 7), info.clone(), ExecuteMsg::EmergencyUnpause {}).unwrap();
+
+        // the 5 paused blocks are added when the proposal is read, up to the cap of 3
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "some title".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.original_expires, Expiration::AtHeight(start + 10));
+        assert_eq!(value.expires, Expiration::AtHeight(start + 13));
+
+        let finalize = ExecuteMsg::FinalizeExpired { limit: None };
+        let res = execute(deps.as_mut(), at(start + 12), info.clone(), finalize.clone()).unwrap();
+        assert_eq!(res.attributes[1].value, "0");
+        // the cap is used up, a second emergency pause leaves the end where it is
+        let _res = execute(deps.as_mut(), at(start + 12), info.clone(), ExecuteMsg::EmergencyPause {}).unwrap();
+        let _res = execute(deps.as_mut(), at(start + 14), info.clone(), ExecuteMsg::EmergencyUnpause {}).unwrap();
+        let res = execute(deps.as_mut(), at(start + 14), info, finalize).unwrap();
+        assert_eq!(res.attributes[1].value, "1");
+    }
+    #[test]
+    fn proper_late_quorum() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let mut category = ProposalCategory {
+            min_votes_count: Uint128::new(2),
+            threshold: Threshold::AbsoluteCount { weight: 1 },
+            veto_threshold: Decimal::percent(34),
+            voting_period: Some(Duration::Height(10)),
+            deposit: None,
+            cancel_refund: RefundPolicy::Always,
+            max_pause_extension: None,
+            late_quorum: Some(LateQuorum { window: Duration::Time(3), extension: Duration::Height(5) }),
+            whitelist_on: false,
+            whitelist: vec![],
+            required_coins_on: false,
+            required_coin: coin(1, "test"),
+            overridable: vec![],
+        };
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category: category.clone() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        assert_eq!(res.unwrap_err(), ContractError::LateQuorumUnitMismatch {});
+        category.late_quorum = Some(LateQuorum { window: Duration::Height(3), extension: Duration::Height(MAX_LATE_QUORUM_BLOCKS + 1) });
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category: category.clone() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+        assert_eq!(res.unwrap_err(), ContractError::LateQuorumExtensionTooLong {});
+        category.late_quorum = Some(LateQuorum { window: Duration::Height(3), extension: Duration::Height(5) });
+        let msg = ExecuteMsg::SetCategory { name: "text".to_string(), category };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        for title in ["late", "early"] {
+            let new_vote = ExecuteMsg::CreateNewVote {
+                title: title.to_string(),
+                category: "text".to_string(),
+                overrides: VoteOverrides::default(),
+                metadata: ProposalMetadata::default(),
+            };
+            let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote).unwrap();
+        }
+
+        let start = mock_env().block.height;
+        let at = |height: u64| {
+            let mut env = mock_env();
+            env.block.height = height;
+            env
+        };
+        let vote = |title: &str| ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: title.to_string()
+        };
+        let _res = execute(deps.as_mut(), at(start + 1), mock_info("user1", &[]), vote("late")).unwrap();
+        let _res = execute(deps.as_mut(), at(start + 1), mock_info("user1", &[]), vote("early")).unwrap();
+        let res = execute(deps.as_mut(), at(start + 2), mock_info("user2", &[]), vote("early")).unwrap();
+        assert_eq!(res.attributes.len(), 1);
+        // quorum reached two blocks before the end, the vote stays open five more blocks
+        let res = execute(deps.as_mut(), at(start + 8), mock_info("user2", &[]), vote("late")).unwrap();
+        assert_eq!(res.attributes[1].key, "late_quorum_extension");
+        let _res = execute(deps.as_mut(), at(start + 12), mock_info("user3", &[]), vote("late")).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "late".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.original_expires, Expiration::AtHeight(start + 10));
+        assert_eq!(value.expires, Expiration::AtHeight(start + 13));
+        assert_eq!(value.quorum_reached_height, Some(start + 8));
+        assert_eq!(value.votes_for, Uint128::new(3));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetVote { title: "early".to_string() }).unwrap();
+        let value: ProposalResponse = from_binary(&res).unwrap();
+        assert_eq!(value.expires, Expiration::AtHeight(start + 10));
+        assert_eq!(value.quorum_reached_height, Some(start + 2));
+        let res = execute(deps.as_mut(), at(start + 12), mock_info("user3", &[]), vote("early"));
+        assert_eq!(res.unwrap_err(), ContractError::VoteExpired {});
+    }
+    #[test]
+    fn proper_empty_whitelist() {
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let msg = InstantiateMsg {
+            admins: vec![]
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let category = ProposalCategory {
+            min_votes_count: Uint128::new(1),
+            threshold: Threshold::AbsolutePercentage { percentage: Decimal::percent(50) },
+            veto_threshold: Decimal::percent(34),
+            voting_period: None,
+            deposit: None,
+            cancel_refund: RefundPolicy::Always,
+            max_pause_extension: None,
+            late_quorum: None,
+            whitelist_on: true,
+            whitelist: vec![Addr::unchecked("user1"), Addr::unchecked("user2")],
+            required_coins_on: false,
+            required_coin: coin(1, "test"),
+            overridable: vec![CategoryField::Whitelist],
+        };
+        let msg = ExecuteMsg::SetCategory { name: "council".to_string(), category };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let new_vote = |title: &str, whitelist: Option<Vec<Addr>>| ExecuteMsg::CreateNewVote {
+            title: title.to_string(),
+            category: "council".to_string(),
+            overrides: VoteOverrides { whitelist, ..VoteOverrides::default() },
+            metadata: ProposalMetadata::default(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote("a", Some(vec![])));
+        assert_eq!(res.unwrap_err(), ContractError::EmptyWhitelist {});
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), new_vote("a", None)).unwrap();
+        let update = ExecuteMsg::UpdateProposal {
+            title: "a".to_string(),
+            update: ProposalUpdate { whitelist: Some(vec![]), ..ProposalUpdate::default() },
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), update);
+        assert_eq!(res.unwrap_err(), ContractError::EmptyWhitelist {});
+        // without the whitelist a percentage has nothing to be measured against
+        let toggle = ExecuteMsg::ToogleWhitelist { title: "a".to_string() };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), toggle);
+        assert_eq!(res.unwrap_err(), ContractError::UnknownTotalPower {});
+
+        // proposals migrated from the first release can still carry an empty whitelist
+        let mut deps = mock_dependencies_with_balances(&[("sdsd", &coins(12, "token"))]);
+        let legacy_config = r#"{"owner":"creator","admins":[],"votes_titles":["b"]}"#;
+        deps.storage.set(b"\x00\x06config", legacy_config.as_bytes());
+        let legacy_vote = r#"{"paused":false,"votes_for":0,"votes_against":0,"votes_abstain":0,"creator":"creator","required_coins_on":false,"required_coins":{"denom":"test","amount":"1"},"min_votes_count":1,"required_votes_percentage":50,"already_participate":[],"whitelist_on":true,"whitelist":[]}"#;
+        deps.storage.set(b"\x00\x05VOTESb", legacy_vote.as_bytes());
+        deps.storage.set(b"\x00\x06stats", br#"{"in_progress":1,"rejected_votes":0,"accepted":0,"not_resolved":0,"paused":0}"#);
+        let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        let vote = ExecuteMsg::Vote {
+            vote: "For".to_string(),
+            title: "b".to_string()
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info.clone(), vote).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Tally { title: "b".to_string() }).unwrap();
+        let value: TallyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.projected_outcome, Some(Outcome::NotResolved));
+        let finalize = ExecuteMsg::Finalize {
+            title: "b".to_string()
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, finalize).unwrap();
+        assert_eq!(res.attributes[1].value, "not_resolved");
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +6,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -44,4 +47,127 @@ pub enum ContractError {
 
     #[error("SenderDoNotHaveEnoughAmount")]
     SenderDoNotHaveEnoughAmount {},
+
+    #[error("VoteAlreadyFinalized")]
+    VoteAlreadyFinalized {},
+
+    #[error("CannotDelegateToSelf")]
+    CannotDelegateToSelf {},
+
+    #[error("DelegationCycle")]
+    DelegationCycle {},
+
+    #[error("DelegationTooDeep")]
+    DelegationTooDeep {},
+
+    #[error("DelegationTreeTooLarge")]
+    DelegationTreeTooLarge {},
+
+    #[error("StatsRecomputeInProgress")]
+    StatsRecomputeInProgress {},
+
+    #[error("NotDelegating")]
+    NotDelegating {},
+
+    #[error("CannotFindCategory")]
+    CannotFindCategory {},
+
+    #[error("FieldNotOverridable")]
+    FieldNotOverridable {},
+
+    #[error("InsufficientDeposit")]
+    InsufficientDeposit {},
+
+    #[error("VoteExpired")]
+    VoteExpired {},
+
+    #[error("VotingPeriodNotOver")]
+    VotingPeriodNotOver {},
+
+    #[error("UnknownTotalPower")]
+    UnknownTotalPower {},
+
+    #[error("EmptyWhitelist")]
+    EmptyWhitelist {},
+
+    #[error("HookAlreadyRegistered")]
+    HookAlreadyRegistered {},
+
+    #[error("HookNotRegistered")]
+    HookNotRegistered {},
+
+    #[error("UnknownReplyId")]
+    UnknownReplyId {},
+
+    #[error("RewardPoolNotConfigured")]
+    RewardPoolNotConfigured {},
+
+    #[error("RewardAssetLocked")]
+    RewardAssetLocked {},
+
+    #[error("InvalidRewardFunds")]
+    InvalidRewardFunds {},
+
+    #[error("NoRewardsToClaim")]
+    NoRewardsToClaim {},
+
+    #[error("WrongContractName")]
+    WrongContractName {},
+
+    #[error("CannotDowngrade")]
+    CannotDowngrade {},
+
+    #[error("InvalidContractVersion")]
+    InvalidContractVersion {},
+
+    #[error("VoteCancelled")]
+    VoteCancelled {},
+
+    #[error("VoteAlreadyStarted")]
+    VoteAlreadyStarted {},
+
+    #[error("NothingToUpdate")]
+    NothingToUpdate {},
+
+    #[error("EmptyTitle")]
+    EmptyTitle {},
+
+    #[error("TitleTooLong")]
+    TitleTooLong {},
+
+    #[error("DescriptionTooLong")]
+    DescriptionTooLong {},
+
+    #[error("InvalidCharacters")]
+    InvalidCharacters {},
+
+    #[error("TooManyLinks")]
+    TooManyLinks {},
+
+    #[error("InvalidLink")]
+    InvalidLink {},
+
+    #[error("TooManyTags")]
+    TooManyTags {},
+
+    #[error("InvalidTag")]
+    InvalidTag {},
+
+    #[error("EmergencyPaused")]
+    EmergencyPaused {},
+
+    #[error("NotEmergencyPaused")]
+    NotEmergencyPaused {},
+
+    #[error("UnpauseDelayNotElapsed")]
+    UnpauseDelayNotElapsed {},
+
+    #[error("PauseCapUnitMismatch")]
+    PauseCapUnitMismatch {},
+
+    #[error("LateQuorumUnitMismatch")]
+    LateQuorumUnitMismatch {},
+
+    #[error("LateQuorumExtensionTooLong")]
+    LateQuorumExtensionTooLong {},
 }
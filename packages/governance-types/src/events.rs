@@ -0,0 +1,367 @@
+use cosmwasm_std::{Addr, Event, StdError, StdResult, Uint128};
+use cw0::Duration;
+use crate::hooks::HookPolicy;
+use crate::types::{Outcome, RewardAsset};
+
+// Event types emitted by the governance contract. The chain prefixes them with `wasm-`.
+pub const PROPOSAL_CREATED: &str = "proposal_created";
+pub const VOTE_CAST: &str = "vote_cast";
+pub const PROPOSAL_PAUSED: &str = "proposal_paused";
+pub const PROPOSAL_UNPAUSED: &str = "proposal_unpaused";
+pub const WHITELIST_TOGGLED: &str = "whitelist_toggled";
+pub const REQUIRED_COIN_TOGGLED: &str = "required_coin_toggled";
+pub const PROPOSAL_FINALIZED: &str = "proposal_finalized";
+pub const PROPOSAL_CANCELLED: &str = "proposal_cancelled";
+pub const PROPOSAL_UPDATED: &str = "proposal_updated";
+pub const DELEGATION_SET: &str = "delegation_set";
+pub const DELEGATION_REMOVED: &str = "delegation_removed";
+pub const CATEGORY_SET: &str = "category_set";
+pub const CATEGORY_REMOVED: &str = "category_removed";
+pub const STATS_RECOMPUTED: &str = "stats_recomputed";
+pub const HOOK_ADDED: &str = "hook_added";
+pub const HOOK_REMOVED: &str = "hook_removed";
+pub const REWARD_POOL_SET: &str = "reward_pool_set";
+pub const REWARDS_FUNDED: &str = "rewards_funded";
+pub const REWARDS_CLAIMED: &str = "rewards_claimed";
+pub const GUARDIAN_SET: &str = "guardian_set";
+pub const EMERGENCY_PAUSED: &str = "emergency_paused";
+pub const EMERGENCY_UNPAUSED: &str = "emergency_unpaused";
+
+const WASM_PREFIX: &str = "wasm-";
+
+// Votes on a proposal after the event was applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TallyAttributes {
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GovernanceEvent {
+    ProposalCreated { title: String, creator: Addr, category: String },
+    VoteCast { title: String, voter: Addr, vote: String, power: Uint128, tally: TallyAttributes },
+    ProposalPaused { title: String, sender: Addr },
+    ProposalUnpaused { title: String, sender: Addr },
+    WhitelistToggled { title: String, enabled: bool },
+    RequiredCoinToggled { title: String, enabled: bool },
+    ProposalFinalized { title: String, outcome: Outcome, tally: TallyAttributes },
+    ProposalCancelled { title: String, sender: Addr, reason: String },
+    ProposalUpdated { title: String, editor: Addr, revision: u64 },
+    DelegationSet { delegator: Addr, delegate: Addr },
+    DelegationRemoved { delegator: Addr, delegate: Addr },
+    CategorySet { name: String },
+    CategoryRemoved { name: String },
+    StatsRecomputed { processed: u64, done: bool },
+    HookAdded { addr: Addr, policy: HookPolicy },
+    HookRemoved { addr: Addr },
+    RewardPoolSet { asset: RewardAsset, per_proposal: Uint128 },
+    RewardsFunded { sender: Addr, amount: Uint128 },
+    RewardsClaimed { voter: Addr, amount: Uint128 },
+    GuardianSet { guardian: Option<Addr>, unpause_delay: Option<Duration> },
+    EmergencyPaused { sender: Addr },
+    EmergencyUnpaused { sender: Addr },
+}
+
+impl GovernanceEvent {
+    pub fn to_event(&self) -> Event {
+        match self {
+            GovernanceEvent::ProposalCreated { title, creator, category } => Event::new(PROPOSAL_CREATED)
+                .add_attribute("title", title)
+                .add_attribute("creator", creator)
+                .add_attribute("category", category),
+            GovernanceEvent::VoteCast { title, voter, vote, power, tally } => add_tally(Event::new(VOTE_CAST)
+                .add_attribute("title", title)
+                .add_attribute("voter", voter)
+                .add_attribute("vote", vote)
+                .add_attribute("power", power.to_string()), tally),
+            GovernanceEvent::ProposalPaused { title, sender } => Event::new(PROPOSAL_PAUSED)
+                .add_attribute("title", title)
+                .add_attribute("sender", sender),
+            GovernanceEvent::ProposalUnpaused { title, sender } => Event::new(PROPOSAL_UNPAUSED)
+                .add_attribute("title", title)
+                .add_attribute("sender", sender),
+            GovernanceEvent::WhitelistToggled { title, enabled } => Event::new(WHITELIST_TOGGLED)
+                .add_attribute("title", title)
+                .add_attribute("enabled", enabled.to_string()),
+            GovernanceEvent::RequiredCoinToggled { title, enabled } => Event::new(REQUIRED_COIN_TOGGLED)
+                .add_attribute("title", title)
+                .add_attribute("enabled", enabled.to_string()),
+            GovernanceEvent::ProposalFinalized { title, outcome, tally } => add_tally(Event::new(PROPOSAL_FINALIZED)
+                .add_attribute("title", title)
+                .add_attribute("outcome", outcome_to_str(outcome)), tally),
+            GovernanceEvent::ProposalCancelled { title, sender, reason } => Event::new(PROPOSAL_CANCELLED)
+                .add_attribute("title", title)
+                .add_attribute("sender", sender)
+                .add_attribute("reason", reason),
+            GovernanceEvent::ProposalUpdated { title, editor, revision } => Event::new(PROPOSAL_UPDATED)
+                .add_attribute("title", title)
+                .add_attribute("editor", editor)
+                .add_attribute("revision", revision.to_string()),
+            GovernanceEvent::DelegationSet { delegator, delegate } => Event::new(DELEGATION_SET)
+                .add_attribute("delegator", delegator)
+                .add_attribute("delegate", delegate),
+            GovernanceEvent::DelegationRemoved { delegator, delegate } => Event::new(DELEGATION_REMOVED)
+                .add_attribute("delegator", delegator)
+                .add_attribute("delegate", delegate),
+            GovernanceEvent::CategorySet { name } => Event::new(CATEGORY_SET)
+                .add_attribute("name", name),
+            GovernanceEvent::CategoryRemoved { name } => Event::new(CATEGORY_REMOVED)
+                .add_attribute("name", name),
+            GovernanceEvent::StatsRecomputed { processed, done } => Event::new(STATS_RECOMPUTED)
+                .add_attribute("processed", processed.to_string())
+                .add_attribute("done", done.to_string()),
+            GovernanceEvent::HookAdded { addr, policy } => Event::new(HOOK_ADDED)
+                .add_attribute("addr", addr)
+                .add_attribute("policy", policy_to_str(policy)),
+            GovernanceEvent::HookRemoved { addr } => Event::new(HOOK_REMOVED)
+                .add_attribute("addr", addr),
+            GovernanceEvent::RewardPoolSet { asset, per_proposal } => add_asset(Event::new(REWARD_POOL_SET), asset)
+                .add_attribute("per_proposal", per_proposal.to_string()),
+            GovernanceEvent::RewardsFunded { sender, amount } => Event::new(REWARDS_FUNDED)
+                .add_attribute("sender", sender)
+                .add_attribute("amount", amount.to_string()),
+            GovernanceEvent::RewardsClaimed { voter, amount } => Event::new(REWARDS_CLAIMED)
+                .add_attribute("voter", voter)
+                .add_attribute("amount", amount.to_string()),
+            // unset values are left out
+            GovernanceEvent::GuardianSet { guardian, unpause_delay } => {
+                let mut event = Event::new(GUARDIAN_SET);
+                if let Some(guardian) = guardian {
+                    event = event.add_attribute("guardian", guardian);
+                }
+                if let Some(delay) = unpause_delay {
+                    event = event.add_attribute("unpause_delay", duration_to_str(delay));
+                }
+                event
+            }
+            GovernanceEvent::EmergencyPaused { sender } => Event::new(EMERGENCY_PAUSED)
+                .add_attribute("sender", sender),
+            GovernanceEvent::EmergencyUnpaused { sender } => Event::new(EMERGENCY_UNPAUSED)
+                .add_attribute("sender", sender),
+        }
+    }
+
+    // Returns None for events which were not emitted by the governance contract.
+    pub fn parse(event: &Event) -> StdResult<Option<GovernanceEvent>> {
+        let ty = event.ty.strip_prefix(WASM_PREFIX).unwrap_or(&event.ty);
+        let parsed = match ty {
+            PROPOSAL_CREATED => GovernanceEvent::ProposalCreated {
+                title: attribute(event, "title")?,
+                creator: Addr::unchecked(attribute(event, "creator")?),
+                category: attribute(event, "category")?,
+            },
+            VOTE_CAST => GovernanceEvent::VoteCast {
+                title: attribute(event, "title")?,
+                voter: Addr::unchecked(attribute(event, "voter")?),
+                vote: attribute(event, "vote")?,
+                power: parse_attribute(event, "power")?,
+                tally: parse_tally(event)?,
+            },
+            PROPOSAL_PAUSED => GovernanceEvent::ProposalPaused {
+                title: attribute(event, "title")?,
+                sender: Addr::unchecked(attribute(event, "sender")?),
+            },
+            PROPOSAL_UNPAUSED => GovernanceEvent::ProposalUnpaused {
+                title: attribute(event, "title")?,
+                sender: Addr::unchecked(attribute(event, "sender")?),
+            },
+            WHITELIST_TOGGLED => GovernanceEvent::WhitelistToggled {
+                title: attribute(event, "title")?,
+                enabled: parse_attribute(event, "enabled")?,
+            },
+            REQUIRED_COIN_TOGGLED => GovernanceEvent::RequiredCoinToggled {
+                title: attribute(event, "title")?,
+                enabled: parse_attribute(event, "enabled")?,
+            },
+            PROPOSAL_FINALIZED => GovernanceEvent::ProposalFinalized {
+                title: attribute(event, "title")?,
+                outcome: outcome_from_str(&attribute(event, "outcome")?)?,
+                tally: parse_tally(event)?,
+            },
+            PROPOSAL_CANCELLED => GovernanceEvent::ProposalCancelled {
+                title: attribute(event, "title")?,
+                sender: Addr::unchecked(attribute(event, "sender")?),
+                reason: attribute(event, "reason")?,
+            },
+            PROPOSAL_UPDATED => GovernanceEvent::ProposalUpdated {
+                title: attribute(event, "title")?,
+                editor: Addr::unchecked(attribute(event, "editor")?),
+                revision: parse_attribute(event, "revision")?,
+            },
+            DELEGATION_SET => GovernanceEvent::DelegationSet {
+                delegator: Addr::unchecked(attribute(event, "delegator")?),
+                delegate: Addr::unchecked(attribute(event, "delegate")?),
+            },
+            DELEGATION_REMOVED => GovernanceEvent::DelegationRemoved {
+                delegator: Addr::unchecked(attribute(event, "delegator")?),
+                delegate: Addr::unchecked(attribute(event, "delegate")?),
+            },
+            CATEGORY_SET => GovernanceEvent::CategorySet {
+                name: attribute(event, "name")?,
+            },
+            CATEGORY_REMOVED => GovernanceEvent::CategoryRemoved {
+                name: attribute(event, "name")?,
+            },
+            STATS_RECOMPUTED => GovernanceEvent::StatsRecomputed {
+                processed: parse_attribute(event, "processed")?,
+                done: parse_attribute(event, "done")?,
+            },
+            HOOK_ADDED => GovernanceEvent::HookAdded {
+                addr: Addr::unchecked(attribute(event, "addr")?),
+                policy: policy_from_str(&attribute(event, "policy")?)?,
+            },
+            HOOK_REMOVED => GovernanceEvent::HookRemoved {
+                addr: Addr::unchecked(attribute(event, "addr")?),
+            },
+            REWARD_POOL_SET => GovernanceEvent::RewardPoolSet {
+                asset: parse_asset(event)?,
+                per_proposal: parse_attribute(event, "per_proposal")?,
+            },
+            REWARDS_FUNDED => GovernanceEvent::RewardsFunded {
+                sender: Addr::unchecked(attribute(event, "sender")?),
+                amount: parse_attribute(event, "amount")?,
+            },
+            REWARDS_CLAIMED => GovernanceEvent::RewardsClaimed {
+                voter: Addr::unchecked(attribute(event, "voter")?),
+                amount: parse_attribute(event, "amount")?,
+            },
+            GUARDIAN_SET => GovernanceEvent::GuardianSet {
+                guardian: optional_attribute(event, "guardian").map(Addr::unchecked),
+                unpause_delay: optional_attribute(event, "unpause_delay")
+                    .map(|delay| duration_from_str(&delay))
+                    .transpose()?,
+            },
+            EMERGENCY_PAUSED => GovernanceEvent::EmergencyPaused {
+                sender: Addr::unchecked(attribute(event, "sender")?),
+            },
+            EMERGENCY_UNPAUSED => GovernanceEvent::EmergencyUnpaused {
+                sender: Addr::unchecked(attribute(event, "sender")?),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(parsed))
+    }
+}
+
+impl From<GovernanceEvent> for Event {
+    fn from(event: GovernanceEvent) -> Self {
+        event.to_event()
+    }
+}
+
+// Parses every governance event of a transaction, skipping the events of other modules.
+pub fn parse_events(events: &[Event]) -> StdResult<Vec<GovernanceEvent>> {
+    events.iter()
+        .filter_map(|event| GovernanceEvent::parse(event).transpose())
+        .collect()
+}
+
+pub fn outcome_to_str(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Accepted => "accepted",
+        Outcome::Rejected => "rejected",
+        Outcome::NotResolved => "not_resolved",
+        Outcome::Vetoed => "vetoed",
+    }
+}
+
+pub fn outcome_from_str(value: &str) -> StdResult<Outcome> {
+    match value {
+        "accepted" => Ok(Outcome::Accepted),
+        "rejected" => Ok(Outcome::Rejected),
+        "not_resolved" => Ok(Outcome::NotResolved),
+        "vetoed" => Ok(Outcome::Vetoed),
+        _ => Err(StdError::parse_err("Outcome", format!("unknown outcome {}", value))),
+    }
+}
+
+pub fn policy_to_str(policy: &HookPolicy) -> &'static str {
+    match policy {
+        HookPolicy::Revert => "revert",
+        HookPolicy::Ignore => "ignore",
+    }
+}
+
+pub fn policy_from_str(value: &str) -> StdResult<HookPolicy> {
+    match value {
+        "revert" => Ok(HookPolicy::Revert),
+        "ignore" => Ok(HookPolicy::Ignore),
+        _ => Err(StdError::parse_err("HookPolicy", format!("unknown policy {}", value))),
+    }
+}
+
+// `height:<blocks>` or `time:<seconds>`.
+pub fn duration_to_str(duration: &Duration) -> String {
+    match duration {
+        Duration::Height(height) => format!("height:{}", height),
+        Duration::Time(time) => format!("time:{}", time),
+    }
+}
+
+pub fn duration_from_str(value: &str) -> StdResult<Duration> {
+    let invalid = || StdError::parse_err("Duration", format!("invalid duration {}", value));
+    let (unit, amount) = value.split_once(':').ok_or_else(invalid)?;
+    let amount = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "height" => Ok(Duration::Height(amount)),
+        "time" => Ok(Duration::Time(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+fn add_tally(event: Event, tally: &TallyAttributes) -> Event {
+    event
+        .add_attribute("votes_for", tally.votes_for.to_string())
+        .add_attribute("votes_against", tally.votes_against.to_string())
+        .add_attribute("votes_abstain", tally.votes_abstain.to_string())
+        .add_attribute("votes_no_with_veto", tally.votes_no_with_veto.to_string())
+}
+
+fn parse_tally(event: &Event) -> StdResult<TallyAttributes> {
+    Ok(TallyAttributes {
+        votes_for: parse_attribute(event, "votes_for")?,
+        votes_against: parse_attribute(event, "votes_against")?,
+        votes_abstain: parse_attribute(event, "votes_abstain")?,
+        votes_no_with_veto: parse_attribute(event, "votes_no_with_veto")?,
+    })
+}
+
+// `asset_type` is native or cw20, `asset` the denom or the token contract.
+fn add_asset(event: Event, asset: &RewardAsset) -> Event {
+    match asset {
+        RewardAsset::Native { denom } => event
+            .add_attribute("asset_type", "native")
+            .add_attribute("asset", denom),
+        RewardAsset::Cw20 { contract_addr } => event
+            .add_attribute("asset_type", "cw20")
+            .add_attribute("asset", contract_addr),
+    }
+}
+
+fn parse_asset(event: &Event) -> StdResult<RewardAsset> {
+    let asset = attribute(event, "asset")?;
+    match attribute(event, "asset_type")?.as_str() {
+        "native" => Ok(RewardAsset::Native { denom: asset }),
+        "cw20" => Ok(RewardAsset::Cw20 { contract_addr: Addr::unchecked(asset) }),
+        value => Err(StdError::parse_err("RewardAsset", format!("unknown asset type {}", value))),
+    }
+}
+
+fn attribute(event: &Event, key: &str) -> StdResult<String> {
+    optional_attribute(event, key)
+        .ok_or_else(|| StdError::parse_err(event.ty.clone(), format!("missing attribute {}", key)))
+}
+
+fn optional_attribute(event: &Event, key: &str) -> Option<String> {
+    event.attributes.iter()
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.clone())
+}
+
+fn parse_attribute<T: std::str::FromStr>(event: &Event, key: &str) -> StdResult<T> {
+    attribute(event, key)?
+        .parse()
+        .map_err(|_| StdError::parse_err(event.ty.clone(), format!("invalid attribute {}", key)))
+}
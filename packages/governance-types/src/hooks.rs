@@ -0,0 +1,64 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, StdResult, Uint128, WasmMsg};
+use crate::types::Outcome;
+
+// What happens to the governance action when a hook contract fails.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HookPolicy {
+    // The whole action is reverted
+    Revert,
+    // The failure is caught in the reply handler and the action goes through
+    Ignore,
+}
+
+// Sent to every registered hook, following cw4 MemberChangedHookMsg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceHookMsg {
+    ProposalCreated {
+        title: String,
+        creator: Addr,
+        category: String,
+    },
+    VoteCast {
+        title: String,
+        voter: Addr,
+        vote: String,
+        power: Uint128,
+    },
+    ProposalFinalized {
+        title: String,
+        outcome: Outcome,
+    },
+    ProposalCancelled {
+        title: String,
+        reason: String,
+    },
+}
+
+impl GovernanceHookMsg {
+    // Serializes the message wrapped in GovernanceHookExecuteMsg, so it can be received by another contract.
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = GovernanceHookExecuteMsg::GovernanceHook(self);
+        to_binary(&msg)
+    }
+
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+// Variant the hook contracts should include in their own ExecuteMsg.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GovernanceHookExecuteMsg {
+    GovernanceHook(GovernanceHookMsg),
+}
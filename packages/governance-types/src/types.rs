@@ -1,6 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128, Uint64};
+use cw0::{Duration, Expiration};
+use cw20::Cw20ReceiveMsg;
+use crate::hooks::HookPolicy;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -12,12 +15,10 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     CreateNewVote { 
         title: String,
-        min_votes_count: i32,
-        required_votes_percentage: i32,
-        whitelist_on: bool,
-        whitelist: Vec<Addr>,
-        required_coins_on: bool,
-        required_coin: Coin,
+        category: String,
+        overrides: VoteOverrides,
+        #[serde(default)]
+        metadata: ProposalMetadata,
     },
     Vote { 
         vote: String, 
@@ -26,7 +27,166 @@ pub enum ExecuteMsg {
     Pause { title: String },
     Unpause { title: String },
     ToogleWhitelist { title: String },
-    ToogleRequiredCoin { title: String }
+    ToogleRequiredCoin { title: String },
+    // Admin only, proposals with a voting period once it is over
+    Finalize { title: String },
+    // Finalizes up to `limit` proposals whose voting period is over, callable by anyone
+    FinalizeExpired { limit: Option<u32> },
+    // Withdraws an open proposal, by its creator or an admin
+    Cancel { title: String, reason: String },
+    // Edits an open proposal, by its creator. Parameters can only change while nobody voted
+    UpdateProposal { title: String, update: ProposalUpdate },
+    Delegate { to: Addr },
+    Undelegate {},
+    SetCategory { name: String, category: ProposalCategory },
+    RemoveCategory { name: String },
+    // Rebuilds Stats and creator stats from the stored proposals, `limit` proposals per call until done.
+    // Proposals cannot change until the recompute is done
+    RecomputeStats { limit: Option<u32> },
+    AddHook { addr: Addr, policy: HookPolicy },
+    RemoveHook { addr: Addr },
+    // Up to `per_proposal` of the pool is split between the voters of each finalized proposal
+    SetRewardPool { asset: RewardAsset, per_proposal: Uint128 },
+    // Funds the native reward pool with the sent coins
+    FundRewards {},
+    // Funds the cw20 reward pool
+    Receive(Cw20ReceiveMsg),
+    // Pays out the rewards of the finalized proposals the sender cast a ballot on, looking at
+    // up to `limit` ballots after `start_after`
+    ClaimRewards { start_after: Option<String>, limit: Option<u32> },
+    // Owner only, sets who can trigger EmergencyPause and how long it lasts at least
+    SetGuardian { guardian: Option<Addr>, unpause_delay: Option<Duration> },
+    // Stops every other message of the contract, by the guardian or the owner
+    EmergencyPause {},
+    // Owner only, once the unpause delay has passed
+    EmergencyUnpause {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    FundRewards {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardAsset {
+    Native { denom: String },
+    Cw20 { contract_addr: Addr },
+}
+
+// Default governance parameters of a named proposal category.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalCategory {
+    pub min_votes_count: Uint128,
+    pub threshold: Threshold,
+    pub veto_threshold: Decimal,
+    pub voting_period: Option<Duration>,
+    pub deposit: Option<Coin>,
+    pub whitelist_on: bool,
+    pub whitelist: Vec<Addr>,
+    pub required_coins_on: bool,
+    pub required_coin: Coin,
+    pub overridable: Vec<CategoryField>,
+    #[serde(default)]
+    pub cancel_refund: RefundPolicy,
+    // Pausing a vote moves its end by the paused blocks or seconds, at most by this much in total.
+    // Uses the unit of voting_period, unlimited when not set
+    #[serde(default)]
+    pub max_pause_extension: Option<Duration>,
+    #[serde(default)]
+    pub late_quorum: Option<LateQuorum>,
+}
+
+// Prevents last-minute swings: when quorum is first reached less than `window` before the end,
+// the vote is kept open for at least `extension` more. Both use the unit of voting_period.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LateQuorum {
+    pub window: Duration,
+    pub extension: Duration,
+}
+
+// What happens to the deposit of a cancelled proposal, deposits which are not refunded are burned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundPolicy {
+    #[default]
+    Always,
+    // Only while no ballot was cast
+    BeforeVotes,
+    Never,
+}
+
+// Passing rules of a vote, following cw3 / cw-utils semantics.
+// Percentages are measured against the total voting power of the vote (the whitelist size),
+// abstain votes count toward the quorum but not toward the threshold.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    // At least `weight` For votes
+    AbsoluteCount { weight: u64 },
+    // For votes reach `percentage` of the total voting power
+    AbsolutePercentage { percentage: Decimal },
+    // Cast votes reach `quorum` of the total voting power and For votes reach `threshold`
+    // of the For / Against / NoWithVeto votes
+    ThresholdQuorum { threshold: Decimal, quorum: Decimal },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryField {
+    MinVotesCount,
+    Threshold,
+    VetoThreshold,
+    VotingPeriod,
+    Whitelist,
+    RequiredCoin,
+}
+
+// Values replacing the category defaults, only allowed for fields listed in `overridable`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct VoteOverrides {
+    pub min_votes_count: Option<Uint128>,
+    pub threshold: Option<Threshold>,
+    pub veto_threshold: Option<Decimal>,
+    pub voting_period: Option<Duration>,
+    pub whitelist_on: Option<bool>,
+    pub whitelist: Option<Vec<Addr>>,
+    pub required_coins_on: Option<bool>,
+    pub required_coin: Option<Coin>,
+}
+
+// Descriptive content of a proposal, it has no effect on the voting rules.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ProposalMetadata {
+    pub description: String,
+    // URLs or IPFS CIDs of the full proposal text and related documents
+    pub links: Vec<String>,
+    // lowercase labels searchable with ProposalsByTag
+    pub tags: Vec<String>,
+}
+
+// Fields changed by UpdateProposal, None leaves the field as it is.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ProposalUpdate {
+    pub description: Option<String>,
+    pub links: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    pub min_votes_count: Option<Uint128>,
+    pub whitelist_on: Option<bool>,
+    pub whitelist: Option<Vec<Addr>>,
+    pub required_coins_on: Option<bool>,
+    pub required_coin: Option<Coin>,
+}
+
+impl ProposalUpdate {
+    pub fn changes_parameters(&self) -> bool {
+        self.min_votes_count.is_some()
+            || self.whitelist_on.is_some()
+            || self.whitelist.is_some()
+            || self.required_coins_on.is_some()
+            || self.required_coin.is_some()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,14 +195,333 @@ pub enum QueryMsg {
     Config {},
     GetStats {},
     GetVotesTitles {},
-    GetVote { title: String }
+    GetVote { title: String },
+    GetDelegation { address: Addr },
+    GetDelegators { delegate: Addr },
+    GetCategory { name: String },
+    GetCategories {},
+    ListProposals {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        status: Option<ProposalStatus>,
+        creator: Option<Addr>,
+    },
+    ReverseProposals {
+        start_before: Option<String>,
+        limit: Option<u32>,
+        status: Option<ProposalStatus>,
+        creator: Option<Addr>,
+    },
+    GetBallot { title: String, voter: Addr },
+    ListVotes {
+        title: String,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    VoterHistory {
+        voter: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Tally { title: String },
+    GetExtendedStats {},
+    GetCreatorStats { creator: Addr },
+    GetProposalBreakdown { title: String },
+    // Differences found by the last completed RecomputeStats
+    VerifyStats {},
+    StatsAt { height: u64 },
+    StatsHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Hooks {},
+    RewardPool {},
+    PendingRewards {
+        address: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    ProposalRevisions {
+        title: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    ProposalsByTag {
+        tag: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Accepted,
+    Rejected,
+    NotResolved,
+    Vetoed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    InProgress,
+    Paused,
+    Accepted,
+    Rejected,
+    NotResolved,
+    Vetoed,
+    Cancelled,
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    pub owner: String,
+    pub owner: Addr,
+    pub admins: Vec<Addr>,
+    pub guardian: Option<Addr>,
+    pub unpause_delay: Option<Duration>,
+    pub emergency_pause: Option<EmergencyPauseState>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmergencyPauseState {
+    pub paused_by: Addr,
+    // None when the owner can unpause right away
+    pub unpause_after: Option<Expiration>,
+    // open proposals are extended by the time since then on unpause
+    #[serde(default)]
+    pub paused_at_height: u64,
+    #[serde(default)]
+    pub paused_at_time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub in_progress: Uint64,
+    pub rejected_votes: Uint64,
+    pub accepted: Uint64,
+    pub not_resolved: Uint64,
+    pub paused: Uint64,
+    pub vetoed: Uint64,
+    pub cancelled: Uint64,
+}
+
+// Stats as recorded at the end of the block `height`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsSnapshot {
+    pub height: u64,
+    pub stats: StatsResponse,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
\ No newline at end of file
+pub struct StatsHistoryResponse {
+    pub snapshots: Vec<StatsSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub title: String,
+    pub status: ProposalStatus,
+    pub creator: Addr,
+    pub category: String,
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
+    pub min_votes_count: Uint128,
+    pub threshold: Threshold,
+    pub veto_threshold: Decimal,
+    pub total_power: Option<u64>,
+    pub whitelist_on: bool,
+    pub whitelist: Vec<Addr>,
+    pub required_coins_on: bool,
+    pub required_coins: Coin,
+    pub deposit: Option<Coin>,
+    // effective end, after the pause extensions
+    pub expires: Expiration,
+    // end set when the proposal was created
+    pub original_expires: Expiration,
+    pub outcome: Option<Outcome>,
+    pub cancel_reason: Option<String>,
+    // block at which the votes first reached min_votes_count
+    pub quorum_reached_height: Option<u64>,
+    pub description: String,
+    pub links: Vec<String>,
+    pub tags: Vec<String>,
+    // number of edits made with UpdateProposal
+    pub revision: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegatorsResponse {
+    pub delegate: Addr,
+    pub delegators: Vec<Addr>,
+    pub total_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalSummary {
+    pub title: String,
+    pub status: ProposalStatus,
+    pub creator: Addr,
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
+    pub expires: Expiration,
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalListResponse {
+    pub proposals: Vec<ProposalSummary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExtendedStatsResponse {
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
+    pub ballots: Uint64,
+    pub finalized: Uint64,
+    // votes cast per finalized proposal
+    pub average_turnout: Decimal,
+    // votes cast over the total voting power, for finalized proposals with a known total power
+    pub participation_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreatorStatsResponse {
+    pub creator: Addr,
+    pub created: Uint64,
+    pub accepted: Uint64,
+    pub rejected: Uint64,
+    pub not_resolved: Uint64,
+    pub vetoed: Uint64,
+    pub cancelled: Uint64,
+    // votes cast per finalized proposal of the creator
+    pub average_turnout: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalBreakdownResponse {
+    pub title: String,
+    pub total_votes: Uint128,
+    pub for_share: Decimal,
+    pub against_share: Decimal,
+    pub abstain_share: Decimal,
+    pub no_with_veto_share: Decimal,
+    // ballots cast directly and power carried by delegates
+    pub ballots: u64,
+    pub delegated: Uint128,
+    pub participation_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsMismatch {
+    pub field: String,
+    pub stored: Uint128,
+    pub recomputed: Uint128,
+}
+
+// Differences the last completed RecomputeStats found and corrected, `height` is None
+// until a recompute completed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyStatsResponse {
+    pub height: Option<u64>,
+    pub consistent: bool,
+    pub mismatches: Vec<StatsMismatch>,
+}
+
+// Current state of a vote, `projected_outcome` is what finalizing it now would record. Finalized
+// proposals return their recorded outcome and cancelled ones None.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TallyResponse {
+    pub title: String,
+    pub votes_for: Uint128,
+    pub votes_against: Uint128,
+    pub votes_abstain: Uint128,
+    pub votes_no_with_veto: Uint128,
+    pub total_votes: Uint128,
+    pub total_power: Option<u64>,
+    // share of the total voting power which voted, None when the total power is unknown
+    pub quorum_reached: Option<Decimal>,
+    // share of For votes the threshold is compared with: For over `weight` for AbsoluteCount,
+    // over the total power for AbsolutePercentage, over For / Against / NoWithVeto for ThresholdQuorum
+    pub threshold_reached: Decimal,
+    pub remaining: Option<Duration>,
+    pub projected_outcome: Option<Outcome>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotInfo {
+    pub title: String,
+    pub voter: Addr,
+    pub vote: String,
+    pub power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotResponse {
+    pub ballot: Option<BallotInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BallotListResponse {
+    pub ballots: Vec<BallotInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HookInfo {
+    pub addr: Addr,
+    pub policy: HookPolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HooksResponse {
+    pub hooks: Vec<HookInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardPoolResponse {
+    pub asset: RewardAsset,
+    pub per_proposal: Uint128,
+    // funds not yet allotted to any proposal
+    pub balance: Uint128,
+    // funds allotted to voters but not claimed yet
+    pub pending: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRewardsResponse {
+    pub address: Addr,
+    pub asset: Option<RewardAsset>,
+    pub amount: Uint128,
+    // title of the last ballot looked at, to pass as `start_after` for the next page.
+    // None once every ballot of the voter was looked at
+    pub last_title: Option<String>,
+}
+
+// One UpdateProposal applied to a proposal, revisions are numbered from 1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalRevision {
+    pub revision: u64,
+    pub editor: Addr,
+    pub height: u64,
+    pub update: ProposalUpdate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalRevisionsResponse {
+    pub revisions: Vec<ProposalRevision>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MigrateMsg {
+    // Rebuilds Stats from the stored proposals, filling the aggregate fields missing from
+    // older deployments. Large deployments should use RecomputeStats instead.
+    #[serde(default)]
+    pub rebuild_stats: bool,
+}